@@ -1,12 +1,15 @@
 // frothy/src/ast.rs
 
-//! Functions and types for parsing frothy programs into [`Ast`](enum.Ast.html)s
+//! Functions and types for parsing frothy programs into [`Ast`](struct.Ast.html)s
 
 use std::fmt;
+use std::mem;
 
 use crate::error::{Error, Result};
+use crate::format::{FormatSpec, Piece};
+use crate::num::{BigInt, Rational};
+use crate::span::{Span, Spanned};
 use crate::token::{Token, Tokens};
-use crate::util::call;
 
 /// Errors in AST building or evaluation
 #[derive(Debug, Clone)]
@@ -34,7 +37,11 @@ impl fmt::Display for AstError {
 #[derive(Debug, Clone)]
 pub enum Literal {
     Boolean(bool),
-    Number(f64),
+    Int(i64),
+    BigInt(BigInt),
+    Rational(Rational),
+    Float(f64),
+    String(String),
     Nil,
 }
 
@@ -43,76 +50,294 @@ impl fmt::Display for Literal {
         // literals are displayed with the default rust formatter
         match self {
             Literal::Boolean(b) => fmt::Display::fmt(b, f),
-            Literal::Number(n) => fmt::Display::fmt(n, f),
+            Literal::Int(n) => fmt::Display::fmt(n, f),
+            Literal::BigInt(n) => fmt::Display::fmt(n, f),
+            Literal::Rational(n) => fmt::Display::fmt(n, f),
+            // same as `Value::Float`, see its `Display` impl in eval.rs
+            Literal::Float(n) => fmt::Debug::fmt(n, f),
+            // strings are quoted and escaped so they can be read back in
+            Literal::String(s) => write!(f, "{:?}", s),
             Literal::Nil => f.write_str("Nil"),
         }
     }
 }
 
+/// Operators which take two operands: `a b <op>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    IntDivide,
+    Modulo,
+    Equals,
+    NotEquals,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::IntDivide => "//",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Equals => "==",
+            BinaryOp::NotEquals => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::And => "and",
+            BinaryOp::Or => "or",
+        })
+    }
+}
+
+/// Operators which take one operand: `a <op>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            UnaryOp::Not => "not",
+        })
+    }
+}
+
+/// Words which rearrange the values on the interpreter's stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackOp {
+    /// ( a -- a a )
+    Dup,
+    /// ( a -- )
+    Drop,
+    /// ( a b -- b a )
+    Swap,
+    /// ( a b -- a b a )
+    Over,
+    /// ( a b c -- b c a )
+    Rot,
+    /// ( a b -- b )
+    Nip,
+    /// ( a b -- b a b )
+    Tuck,
+    /// ( -- n ) where `n` is the number of values on the stack
+    Depth,
+    /// ( ... -- )
+    Clear,
+}
+
+impl fmt::Display for StackOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            StackOp::Dup => "dup",
+            StackOp::Drop => "drop",
+            StackOp::Swap => "swap",
+            StackOp::Over => "over",
+            StackOp::Rot => "rot",
+            StackOp::Nip => "nip",
+            StackOp::Tuck => "tuck",
+            StackOp::Depth => "depth",
+            StackOp::Clear => "clear",
+        })
+    }
+}
+
+/// A frothy AST node and the [`Span`](../span/struct.Span.html) of program it was parsed from
+#[derive(Debug, Clone)]
+pub struct Ast {
+    pub kind: AstKind,
+    pub span: Span,
+}
+
+impl Ast {
+    pub fn new(kind: AstKind, span: Span) -> Ast {
+        Ast { kind, span }
+    }
+}
+
+impl fmt::Display for Ast {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.kind, f)
+    }
+}
+
+/// A part of an interpolated string
+#[derive(Debug, Clone)]
+pub enum Segment {
+    Text(String),
+    /// A variable lookup and how to format its value
+    Field(Ast, FormatSpec),
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Segment::Text(text) => fmt::Display::fmt(&Piece::Text(text.clone()), f),
+            Segment::Field(ast, spec) => {
+                fmt::Display::fmt(&Piece::Field(ast.to_string(), *spec), f)
+            }
+        }
+    }
+}
+
 /// Frothy AST node types
 #[derive(Debug, Clone)]
-pub enum Ast {
+pub enum AstKind {
     Literal(Literal),
 
     // operations
-    Add(Box<Ast>, Box<Ast>),
-    Subtract(Box<Ast>, Box<Ast>),
-    Multiply(Box<Ast>, Box<Ast>),
-    Divide(Box<Ast>, Box<Ast>),
+    Binary(BinaryOp, Box<Ast>, Box<Ast>),
+    Unary(UnaryOp, Box<Ast>),
 
-    Func(Vec<Ast>),
+    // functions: parameter names and body
+    Func(Vec<String>, Vec<Ast>),
     Call(Box<Ast>),
+    // the parameters at the start of a block, which are moved into `Func` by `fn`
+    Params(Vec<String>),
+
+    // control flow, where the branches are `Block`s
+    If(Box<Ast>, Box<Ast>),
+    IfElse(Box<Ast>, Box<Ast>, Box<Ast>),
+    While(Box<Ast>, Box<Ast>),
+    Times(Box<Ast>, Box<Ast>),
+    For(String, Box<Ast>, Box<Ast>, Box<Ast>),
+    Break,
+    Continue,
 
     // variables
     Ident(String),
     Assign(String, Box<Ast>),
+    Reassign(String, Box<Ast>),
+    // a value which is assigned as a constant
+    Const(Box<Ast>),
 
     Block(Vec<Ast>),
+    // a list of the values left by its items
+    List(Vec<Ast>),
+    // a map of the values left by its items, which alternate between keys and values
+    Map(Vec<Ast>),
+    // an interpolated string
+    Interpolate(Vec<Segment>),
+
+    // the runtime stack
+    Stack(StackOp),
+    // an operand which is already on the stack when its operator is evaluated
+    FromStack,
 }
 
-impl fmt::Display for Ast {
+impl fmt::Display for AstKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             // lit
-            Ast::Literal(lit) => fmt::Display::fmt(lit, f),
-            // (a b +)
-            Ast::Add(a, b) => write!(f, "({} {} +)", a, b),
-            // (a b -)
-            Ast::Subtract(a, b) => write!(f, "({} {} -)", a, b),
-            // (a b *)
-            Ast::Multiply(a, b) => write!(f, "({} {} *)", a, b),
-            // (a b /)
-            Ast::Divide(a, b) => write!(f, "({} {} /)", a, b),
+            AstKind::Literal(lit) => fmt::Display::fmt(lit, f),
+            // (a b op)
+            AstKind::Binary(op, a, b) => write!(f, "({} {} {})", a, b, op),
+            // (a op)
+            AstKind::Unary(op, a) => write!(f, "({} {})", a, op),
             // {ast+}
-            Ast::Block(block) => {
+            AstKind::Block(block) => {
                 f.write_str("{")?;
                 for ast in block {
                     write!(f, "{}", ast)?;
                 }
                 f.write_str("}")
             }
-            // ({ast+} fn)
-            Ast::Func(block) => {
+            // [ast*]
+            AstKind::List(items) => {
+                f.write_str("[")?;
+                for (i, ast) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", ast)?;
+                }
+                f.write_str("]")
+            }
+            // %{ast*}
+            AstKind::Map(items) => {
+                f.write_str("%{")?;
+                for (i, ast) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", ast)?;
+                }
+                f.write_str("}")
+            }
+            // f"segment*"
+            AstKind::Interpolate(segments) => {
+                let template: String = segments.iter().map(Segment::to_string).collect();
+                write!(f, "f{:?}", template)
+            }
+            // ({ident* -> ast+} fn)
+            AstKind::Func(params, block) => {
                 f.write_str("({")?;
+                if !params.is_empty() {
+                    write!(f, "{} -> ", params.join(" "))?;
+                }
                 for ast in block {
                     write!(f, "{}", ast)?;
                 }
                 f.write_str("} fn)")
             }
+            // ident* ->
+            AstKind::Params(params) => write!(f, "{} ->", params.join(" ")),
             // (ast call)
-            Ast::Call(ast) => write!(f, "({} call)", ast),
+            AstKind::Call(ast) => write!(f, "({} call)", ast),
+            // (cond {then} if)
+            AstKind::If(cond, then) => write!(f, "({} {} if)", cond, then),
+            // (cond {then} {else} ifelse)
+            AstKind::IfElse(cond, then, otherwise) => {
+                write!(f, "({} {} {} ifelse)", cond, then, otherwise)
+            }
+            // ({cond} {body} while)
+            AstKind::While(cond, body) => write!(f, "({} {} while)", cond, body),
+            // (n {body} times)
+            AstKind::Times(count, body) => write!(f, "({} {} times)", count, body),
+            // (ident start end {body} for)
+            AstKind::For(ident, start, end, body) => {
+                write!(f, "({} {} {} {} for)", ident, start, end, body)
+            }
+            AstKind::Stack(op) => fmt::Display::fmt(op, f),
+            // _
+            AstKind::FromStack => f.write_str("_"),
+            AstKind::Break => f.write_str("break"),
+            AstKind::Continue => f.write_str("continue"),
             // (ident =)
-            Ast::Assign(ident, value) => write!(f, "({} {} =)", ident, value),
+            AstKind::Assign(ident, value) => write!(f, "({} {} =)", ident, value),
+            // (ident :=)
+            AstKind::Reassign(ident, value) => write!(f, "({} {} :=)", ident, value),
+            // (ast const)
+            AstKind::Const(value) => write!(f, "({} const)", value),
             // ident
-            Ast::Ident(ident) => f.write_str(ident),
+            AstKind::Ident(ident) => f.write_str(ident),
         }
     }
 }
 
 /// Parse a frothy program into [`ast::Ast`]
+///
+/// Operators take their operands from the `Ast`s before them in the same block. Any operands
+/// which are missing are instead taken from the interpreter's stack when the operator is
+/// evaluated.
 pub struct Parser<'a> {
     tokens: Tokens<'a>,
     stack: Vec<Ast>,
+    // the position in `stack` where the block currently being parsed begins
+    block_start: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -120,6 +345,7 @@ impl<'a> Parser<'a> {
         Parser {
             tokens: Tokens::new(program),
             stack: vec![],
+            block_start: 0,
         }
     }
 
@@ -129,118 +355,454 @@ impl<'a> Parser<'a> {
             match self.parse_next() {
                 // parsed successfully, no action required as the results should be on the stack
                 Ok(_) => {}
-                // nothing to parse, we have reached the end of the program
-                Err(Error::Ast(AstError::UnexpectedEoi)) => return Ok(self.stack),
-                // failed to parse for some other reason
-                Err(e) => return Err(e),
+                // failed to parse, which might just mean we reached the end of the program
+                Err(e) => {
+                    return match e.inner() {
+                        // nothing to parse, we have reached the end of the program
+                        Error::Ast(AstError::UnexpectedEoi) => Ok(self.stack),
+                        // failed to parse for some other reason
+                        _ => Err(e),
+                    };
+                }
             }
         }
     }
 
-    // parse the next valid ast
+    // parse the next valid ast, tagging any error with the span of the offending token
     fn parse_next(&mut self) -> Result<()> {
-        // macro to easily define a binary op (a b <op>) using `util::call`
+        let Spanned { node: token, span } = match self.tokens.next() {
+            Some(token) => token?,
+            None => return Err(AstError::UnexpectedEoi.into()),
+        };
+
+        self.parse_token(token, span).map_err(|e| e.at(span))
+    }
+
+    // pop an `Ast` belonging to the current block
+    fn pop(&mut self) -> Option<Ast> {
+        if self.stack.len() > self.block_start {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    // pop an operand for the operator at `span`, or take it from the stack at runtime if
+    // the current block doesn't have one
+    fn pop_operand(&mut self, span: Span) -> Ast {
+        self.pop()
+            .unwrap_or_else(|| Ast::new(AstKind::FromStack, span))
+    }
+
+    // parse the ast(s) beginning with `token`
+    fn parse_token(&mut self, token: Token, span: Span) -> Result<()> {
+        // macros to easily define an op (a b <op>) using `pop_operand`
+        // the resulting node spans from the first operand to the operator
         macro_rules! binary_op {
-            ($variant:ident) => {{
-                let _ = call(&mut self.stack, 2, |a| {
-                    Ast::$variant(Box::new(a[0].clone()), Box::new(a[1].clone()))
-                })?;
+            ($op:ident) => {{
+                let b = self.pop_operand(span);
+                let a = self.pop_operand(span);
+                let a_span = a.span;
+                self.stack.push(Ast::new(
+                    AstKind::Binary(BinaryOp::$op, Box::new(a), Box::new(b)),
+                    a_span.to(span),
+                ));
+            }};
+        }
+        macro_rules! unary_op {
+            ($op:ident) => {{
+                let a = self.pop_operand(span);
+                let a_span = a.span;
+                self.stack.push(Ast::new(
+                    AstKind::Unary(UnaryOp::$op, Box::new(a)),
+                    a_span.to(span),
+                ));
             }};
         }
 
-        if let Some(token) = self.tokens.next() {
-            match token? {
-                // a b +
-                Token::Plus => binary_op!(Add),
-                // a b -
-                Token::Minus => binary_op!(Subtract),
-                // a b *
-                Token::Multiply => binary_op!(Multiply),
-                // a b /
-                Token::Divide => binary_op!(Divide),
-                // { <block> }
-                Token::OpenBrace => self.parse_block()?,
-                // identifier is either a keyword or a variable name
-                Token::Ident(ident) => {
-                    match ident.as_ref() {
-                        // keywords
-                        "fn" => self.parse_fn()?,
-                        "call" => self.parse_call()?,
-                        // keyword literals
-                        "Nil" => self.stack.push(Ast::Literal(Literal::Nil)),
-                        "true" => self.stack.push(Ast::Literal(Literal::Boolean(true))),
-                        "false" => self.stack.push(Ast::Literal(Literal::Boolean(false))),
-                        // default is ident
-                        _ => self.stack.push(Ast::Ident(ident)),
-                    }
-                }
-                // number
-                Token::Number(num) => self.stack.push(Ast::Literal(Literal::Number(num))),
-                // ident ast =
-                Token::Assign => {
-                    // expect an assign: ident + ast
-                    if let (Some(ast), Some(Ast::Ident(ident))) =
-                    (self.stack.pop(), self.stack.pop())
-                    {
-                        self.stack.push(Ast::Assign(ident, Box::new(ast)));
-                    } else {
-                        return Err(AstError::Expected(String::from("ident + ast")).into());
-                    }
+        // push a node which is made up only of the current token
+        macro_rules! push {
+            ($kind:expr) => {
+                self.stack.push(Ast::new($kind, span))
+            };
+        }
+
+        match token {
+            // a b +
+            Token::Plus => binary_op!(Add),
+            // a b -
+            Token::Minus => binary_op!(Subtract),
+            // a b *
+            Token::Multiply => binary_op!(Multiply),
+            // a b /
+            Token::Divide => binary_op!(Divide),
+            // a b //
+            Token::IntDivide => binary_op!(IntDivide),
+            // a b %
+            Token::Modulo => binary_op!(Modulo),
+            // comparisons: a b ==
+            Token::Equals => binary_op!(Equals),
+            Token::NotEquals => binary_op!(NotEquals),
+            Token::Less => binary_op!(Less),
+            Token::LessEqual => binary_op!(LessEqual),
+            Token::Greater => binary_op!(Greater),
+            Token::GreaterEqual => binary_op!(GreaterEqual),
+            // { <block> }
+            Token::OpenBrace => self.parse_block(span)?,
+            // [ <items> ]
+            Token::OpenBracket => self.parse_items(span, Token::CloseBracket, AstKind::List)?,
+            // %{ <keys and values> }
+            Token::OpenMap => self.parse_items(span, Token::CloseBrace, AstKind::Map)?,
+            // identifier is either a keyword or a variable name
+            Token::Ident(ident) => {
+                match ident.as_ref() {
+                    // keywords
+                    "fn" => self.parse_fn(span)?,
+                    "call" => self.parse_call(span)?,
+                    "const" => self.parse_const(span)?,
+                    "if" => self.parse_if(span)?,
+                    "ifelse" => self.parse_ifelse(span)?,
+                    "while" => self.parse_while(span)?,
+                    "times" => self.parse_times(span)?,
+                    "for" => self.parse_for(span)?,
+                    "break" => push!(AstKind::Break),
+                    "continue" => push!(AstKind::Continue),
+                    // boolean operators
+                    "and" => binary_op!(And),
+                    "or" => binary_op!(Or),
+                    "not" => unary_op!(Not),
+                    // stack manipulation
+                    "dup" => push!(AstKind::Stack(StackOp::Dup)),
+                    "drop" => push!(AstKind::Stack(StackOp::Drop)),
+                    "swap" => push!(AstKind::Stack(StackOp::Swap)),
+                    "over" => push!(AstKind::Stack(StackOp::Over)),
+                    "rot" => push!(AstKind::Stack(StackOp::Rot)),
+                    "nip" => push!(AstKind::Stack(StackOp::Nip)),
+                    "tuck" => push!(AstKind::Stack(StackOp::Tuck)),
+                    "depth" => push!(AstKind::Stack(StackOp::Depth)),
+                    "clear" => push!(AstKind::Stack(StackOp::Clear)),
+                    // keyword literals
+                    "Nil" => push!(AstKind::Literal(Literal::Nil)),
+                    "true" => push!(AstKind::Literal(Literal::Boolean(true))),
+                    "false" => push!(AstKind::Literal(Literal::Boolean(false))),
+                    // default is ident
+                    _ => push!(AstKind::Ident(ident)),
                 }
-                // unexpected token
-                token => return Err(AstError::Unexpected(token).into()),
             }
-        } else {
-            return Err(AstError::UnexpectedEoi.into());
+            // numbers
+            Token::Int(int) => push!(AstKind::Literal(Literal::Int(int))),
+            Token::BigInt(int) => push!(AstKind::Literal(Literal::BigInt(int))),
+            Token::Rational(rational) => push!(AstKind::Literal(Literal::Rational(rational))),
+            Token::Float(float) => push!(AstKind::Literal(Literal::Float(float))),
+            // string
+            Token::String(string) => push!(AstKind::Literal(Literal::String(string))),
+            // f"text {ident:spec}"
+            Token::FormatString(pieces) => {
+                let segments = pieces
+                    .into_iter()
+                    .map(|piece| match piece {
+                        Piece::Text(text) => Segment::Text(text),
+                        Piece::Field(ident, spec) => {
+                            Segment::Field(Ast::new(AstKind::Ident(ident), span), spec)
+                        }
+                    })
+                    .collect();
+                push!(AstKind::Interpolate(segments))
+            }
+            // ident ast =
+            Token::Assign => self.parse_assign(span, AstKind::Assign)?,
+            // ident ast :=
+            Token::Reassign => self.parse_assign(span, AstKind::Reassign)?,
+            // unexpected token
+            token => return Err(AstError::Unexpected(token).into()),
         }
 
         Ok(())
     }
 
-    // parse a block: { <ast>* }
-    fn parse_block(&mut self) -> Result<()> {
+    // parse an assignment: <ident> <ast> = or <ident> <ast> :=
+    fn parse_assign<F>(&mut self, span: Span, kind: F) -> Result<()>
+    where
+        F: FnOnce(String, Box<Ast>) -> AstKind,
+    {
+        // expect an assign: ident + ast
+        match (self.pop(), self.pop()) {
+            (
+                Some(ast),
+                Some(Ast {
+                    kind: AstKind::Ident(ident),
+                    span: ident_span,
+                }),
+            ) => {
+                self.stack
+                    .push(Ast::new(kind(ident, Box::new(ast)), ident_span.to(span)));
+                Ok(())
+            }
+            _ => Err(AstError::Expected(String::from("ident + ast")).into()),
+        }
+    }
+
+    // parse a block: { <ast>* } or { <ident>* -> <ast>* } fn
+    fn parse_block(&mut self, open: Span) -> Result<()> {
         // mark the beginning of the block contents in the stack
         let start = self.stack.len();
+        let outer = mem::replace(&mut self.block_start, start);
+        let result = self.parse_block_contents(open, start);
+        self.block_start = outer;
+        result
+    }
 
+    // parse the rest of a block whose contents begin at `start` in the stack
+    fn parse_block_contents(&mut self, open: Span, start: usize) -> Result<()> {
         loop {
             // if we can read a '}' token, push the block containing all `Ast`s
             // on the stack pushed after `start`
-            if let Ok(Token::CloseBrace) = self.tokens.clone().peekable().peek().unwrap() {
-                // pop the asts added since `start` from the stack
-                let block = (&self.stack[start..]).to_vec();
-                self.stack.drain(start..);
+            match self.tokens.clone().next() {
+                Some(Ok(Spanned {
+                    node: Token::CloseBrace,
+                    span: close,
+                })) => {
+                    // pop the asts added since `start` from the stack
+                    let block: Vec<Ast> = self.stack.drain(start..).collect();
+                    let span = open.to(close);
 
-                // push the block to the stack
-                self.stack.push(Ast::Block(block));
+                    // skip the '}'
+                    self.tokens.next();
 
-                // skip the '}'
-                self.tokens.next();
+                    // only functions can have parameters, so `fn` must come next
+                    if let Some(AstKind::Params(_)) = block.first().map(|ast| &ast.kind) {
+                        match self.tokens.clone().next() {
+                            Some(Ok(Spanned {
+                                node: Token::Ident(ref ident),
+                                ..
+                            })) if ident == "fn" => {}
+                            _ => {
+                                return Err(Error::from(AstError::Expected(String::from(
+                                    "fn after a block with parameters",
+                                )))
+                                .at(span));
+                            }
+                        }
+                    }
+
+                    // push the block to the stack
+                    self.stack.push(Ast::new(AstKind::Block(block), span));
 
-                return Ok(());
+                    return Ok(());
+                }
+                // the identifiers read so far are parameters
+                Some(Ok(Spanned {
+                    node: Token::Arrow,
+                    span: arrow,
+                })) => {
+                    self.tokens.next();
+                    self.parse_params(start, arrow)?;
+                    continue;
+                }
+                // EOI before the block was closed
+                None => {
+                    let eoi = self.tokens.span_here();
+                    return Err(Error::from(AstError::Expected(String::from("}"))).at(eoi));
+                }
+                _ => {}
             }
 
-            // we didn't return, so there was no '}'. parse the next Ast, and
-            // if EOI is reached, return syntax error
-            if let Err(Error::Ast(AstError::UnexpectedEoi)) = self.parse_next() {
-                return Err(AstError::Expected(String::from("}")).into());
+            // we didn't return, so there was no '}'. parse the next Ast
+            self.parse_next()?;
+        }
+    }
+
+    // parse the items of a list or map: [ <ast>* ] or %{ <ast>* }
+    fn parse_items(
+        &mut self,
+        open: Span,
+        close: Token,
+        kind: fn(Vec<Ast>) -> AstKind,
+    ) -> Result<()> {
+        // like a block, the items can't take operands from outside it
+        let start = self.stack.len();
+        let outer = mem::replace(&mut self.block_start, start);
+        let result = self.parse_items_until(open, start, close, kind);
+        self.block_start = outer;
+        result
+    }
+
+    // parse the rest of the items which begin at `start` in the stack, up to `close`
+    fn parse_items_until(
+        &mut self,
+        open: Span,
+        start: usize,
+        close: Token,
+        kind: fn(Vec<Ast>) -> AstKind,
+    ) -> Result<()> {
+        loop {
+            match self.tokens.clone().next() {
+                // the end of the items, so push them with all `Ast`s pushed after `start`
+                Some(Ok(Spanned { node, span })) if node == close => {
+                    self.tokens.next();
+                    let items = self.stack.drain(start..).collect();
+                    self.stack.push(Ast::new(kind(items), open.to(span)));
+                    return Ok(());
+                }
+                // EOI before the items were closed
+                None => {
+                    let eoi = self.tokens.span_here();
+                    return Err(Error::from(AstError::Expected(close.to_string())).at(eoi));
+                }
+                _ => self.parse_next()?,
             }
         }
     }
 
+    // replace the identifiers at the start of a block with a parameter list
+    fn parse_params(&mut self, start: usize, arrow: Span) -> Result<()> {
+        let mut params = vec![];
+
+        for ast in self.stack.drain(start..) {
+            match ast.kind {
+                AstKind::Ident(ident) => params.push(ident),
+                _ => {
+                    return Err(Error::from(AstError::Expected(String::from(
+                        "parameter names before '->'",
+                    )))
+                    .at(arrow));
+                }
+            }
+        }
+
+        self.stack.push(Ast::new(AstKind::Params(params), arrow));
+        Ok(())
+    }
+
     // parse a function expression: { <asts> } fn
-    fn parse_fn(&mut self) -> Result<()> {
-        if let Some(Ast::Block(block)) = self.stack.pop() {
-            self.stack.push(Ast::Func(block));
+    fn parse_fn(&mut self, span: Span) -> Result<()> {
+        if let Some(Ast {
+            kind: AstKind::Block(mut block),
+            span: block_span,
+        }) = self.pop()
+        {
+            // take the parameters from the start of the block, if there are any
+            let params = match block.first() {
+                Some(Ast {
+                    kind: AstKind::Params(params),
+                    ..
+                }) => {
+                    let params = params.clone();
+                    block.remove(0);
+                    params
+                }
+                _ => vec![],
+            };
+
+            self.stack
+                .push(Ast::new(AstKind::Func(params, block), block_span.to(span)));
         } else {
             return Err(AstError::Expected(String::from("block")).into());
         }
         Ok(())
     }
 
+    // parse a conditional: <cond> { <then> } if
+    fn parse_if(&mut self, span: Span) -> Result<()> {
+        let then = self.pop_block()?;
+        let cond = self.pop_operand(span);
+        let cond_span = cond.span;
+        self.stack.push(Ast::new(
+            AstKind::If(Box::new(cond), Box::new(then)),
+            cond_span.to(span),
+        ));
+        Ok(())
+    }
+
+    // parse a conditional with an alternative: <cond> { <then> } { <else> } ifelse
+    fn parse_ifelse(&mut self, span: Span) -> Result<()> {
+        let otherwise = self.pop_block()?;
+        let then = self.pop_block()?;
+        let cond = self.pop_operand(span);
+        let cond_span = cond.span;
+        self.stack.push(Ast::new(
+            AstKind::IfElse(Box::new(cond), Box::new(then), Box::new(otherwise)),
+            cond_span.to(span),
+        ));
+        Ok(())
+    }
+
+    // parse a loop which runs while a condition holds: { <cond> } { <body> } while
+    fn parse_while(&mut self, span: Span) -> Result<()> {
+        let body = self.pop_block()?;
+        let cond = self.pop_block()?;
+        let cond_span = cond.span;
+        self.stack.push(Ast::new(
+            AstKind::While(Box::new(cond), Box::new(body)),
+            cond_span.to(span),
+        ));
+        Ok(())
+    }
+
+    // parse a loop which runs a fixed number of times: <n> { <body> } times
+    fn parse_times(&mut self, span: Span) -> Result<()> {
+        let body = self.pop_block()?;
+        let count = self.pop_operand(span);
+        let count_span = count.span;
+        self.stack.push(Ast::new(
+            AstKind::Times(Box::new(count), Box::new(body)),
+            count_span.to(span),
+        ));
+        Ok(())
+    }
+
+    // parse a loop over a range, assigning each index to `ident`:
+    // <ident> <start> <end> { <body> } for
+    fn parse_for(&mut self, span: Span) -> Result<()> {
+        let body = self.pop_block()?;
+        let end = self.pop().ok_or(Error::NotEnoughArguments(4, 1))?;
+        let start = self.pop().ok_or(Error::NotEnoughArguments(4, 2))?;
+
+        match self.pop() {
+            Some(Ast {
+                kind: AstKind::Ident(ident),
+                span: ident_span,
+            }) => {
+                self.stack.push(Ast::new(
+                    AstKind::For(ident, Box::new(start), Box::new(end), Box::new(body)),
+                    ident_span.to(span),
+                ));
+                Ok(())
+            }
+            _ => Err(AstError::Expected(String::from("ident + start + end + block")).into()),
+        }
+    }
+
+    // pop a block from the stack for use as the body of a control flow expression
+    fn pop_block(&mut self) -> Result<Ast> {
+        match self.pop() {
+            Some(
+                ast @ Ast {
+                    kind: AstKind::Block(_),
+                    ..
+                },
+            ) => Ok(ast),
+            _ => Err(AstError::Expected(String::from("block")).into()),
+        }
+    }
+
+    // parse a constant value: <ast> const
+    fn parse_const(&mut self, span: Span) -> Result<()> {
+        let value = self.pop().ok_or(Error::NotEnoughArguments(1, 0))?;
+        let value_span = value.span;
+        self.stack.push(Ast::new(
+            AstKind::Const(Box::new(value)),
+            value_span.to(span),
+        ));
+        Ok(())
+    }
+
     // parse a call expression: <ident> call
-    fn parse_call(&mut self) -> Result<()> {
-        let arg = self.stack.pop().ok_or(Error::NotEnoughArguments(1, 0))?;
-        self.stack.push(Ast::Call(Box::new(arg)));
+    fn parse_call(&mut self, span: Span) -> Result<()> {
+        let arg = self.pop_operand(span);
+        let arg_span = arg.span;
+        self.stack
+            .push(Ast::new(AstKind::Call(Box::new(arg)), arg_span.to(span)));
         Ok(())
     }
 }
@@ -1,22 +1,47 @@
 //! frothy
 //!
 //! TODO: finish parse functions
-//! TODO: add 'near x' parse error messages by keeping token position and last token
 
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
+use std::process;
+
+use frothy::diagnostic::{ColorChoice, Diagnostic};
+
+const USAGE: &str = "usage: frothy [--color=auto|always|never] <path>";
 
 fn main() {
+    // parse the arguments: an optional color choice and the path of the program to run
+    let mut color = ColorChoice::Auto;
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        if let Some(choice) = arg.strip_prefix("--color=") {
+            color = choice.parse().unwrap_or_else(|e| {
+                eprintln!("{}\n{}", e, USAGE);
+                process::exit(2);
+            });
+        } else if path.is_none() {
+            path = Some(arg);
+        } else {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+    let path = path.expect(USAGE);
+
     // read the file at the path argument
-    let path = env::args().nth(1).expect("usage: frothy <path>");
-    let mut file = File::open(path).expect("file not found");
+    let mut file = File::open(&path).expect("file not found");
     let mut program = String::new();
     file.read_to_string(&mut program)
         .expect("failed to read file");
 
     match frothy::eval(&program) {
         Ok(values) => println!("values: {:?}", values),
-        Err(e) => eprintln!("error: {}", e),
+        Err(e) => {
+            let diagnostic = Diagnostic::from(&e);
+            eprint!("{}", diagnostic.render(&path, &program, color.enabled()));
+            process::exit(1);
+        }
     }
 }
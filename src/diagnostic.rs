@@ -0,0 +1,282 @@
+//! Render an [`Error`](../error/enum.Error.html) against the program it occurred in
+//!
+//! The output is modelled on rustc's diagnostics:
+//!
+//! ```text
+//! error: undefined variable 'r'
+//!  --> area.fy:2:5
+//!   |
+//! 2 |     r r * PI *
+//!   |     ^
+//!   |
+//!   = help: variables must be assigned before they are used, e.g. `r 5 =`
+//! ```
+
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use crate::ast::AstError;
+use crate::error::Error;
+use crate::eval::InterpreterError;
+use crate::format::FormatError;
+use crate::span::Span;
+
+// ansi escape codes used when rendering with color
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// tabs are expanded so that carets line up with the source line above them
+const TAB: &str = "    ";
+
+/// Whether diagnostics should be rendered with ansi colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use color only if stderr is a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolve the choice into whether colors should actually be used
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> ::std::result::Result<ColorChoice, String> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "invalid color choice '{}' (expected auto, always or never)",
+                s
+            )),
+        }
+    }
+}
+
+/// An error message with an optional location and help note
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new<T: Into<String>>(message: T, span: Option<Span>) -> Diagnostic {
+        Diagnostic {
+            message: message.into(),
+            span,
+            help: None,
+        }
+    }
+
+    /// Attach a help note, replacing any existing one
+    pub fn with_help<T: Into<String>>(mut self, help: T) -> Diagnostic {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Render the diagnostic for the program `source` read from the file `name`
+    pub fn render(&self, name: &str, source: &str, color: bool) -> String {
+        // only emit escape codes when rendering with color
+        let paint = |code: &'static str| if color { code } else { "" };
+        let (red, blue, bold, reset) = (paint(RED), paint(BLUE), paint(BOLD), paint(RESET));
+
+        let mut out = String::new();
+
+        // error: <message>
+        let _ = writeln!(
+            out,
+            "{}error{}{}: {}{}",
+            red, reset, bold, self.message, reset
+        );
+
+        // without a location there is no snippet to show
+        let span = match self.span {
+            Some(span) => span,
+            None => {
+                let _ = writeln!(out, " {}-->{} {}", blue, reset, name);
+                self.render_help(&mut out, 0, blue, bold, reset);
+                return out;
+            }
+        };
+
+        // the gutter is as wide as the line number
+        let line_no = span.line.to_string();
+        let pad = " ".repeat(line_no.len());
+        let line = source.lines().nth(span.line - 1).unwrap_or("");
+
+        // --> file:line:col
+        let _ = writeln!(out, "{}{}-->{} {}:{}", pad, blue, reset, name, span);
+        let _ = writeln!(out, "{} {}|{}", pad, blue, reset);
+
+        // n | <source line>
+        let _ = writeln!(
+            out,
+            "{}{} |{} {}",
+            blue,
+            line_no,
+            reset,
+            line.replace('\t', TAB)
+        );
+
+        // underline the span, stopping at the end of the line if it spans several
+        let (indent, width) = underline(line, span);
+        let _ = writeln!(
+            out,
+            "{} {}|{} {}{}{}{}",
+            pad,
+            blue,
+            reset,
+            " ".repeat(indent),
+            red,
+            "^".repeat(width),
+            reset
+        );
+
+        self.render_help(&mut out, pad.len(), blue, bold, reset);
+        out
+    }
+
+    //   |
+    //   = help: <help>
+    fn render_help(&self, out: &mut String, pad: usize, blue: &str, bold: &str, reset: &str) {
+        if let Some(help) = &self.help {
+            let pad = " ".repeat(pad);
+            let _ = writeln!(out, "{} {}|{}", pad, blue, reset);
+            let _ = writeln!(
+                out,
+                "{} {}={} {}help{}: {}",
+                pad, blue, reset, bold, reset, help
+            );
+        }
+    }
+}
+
+impl<'a> From<&'a Error> for Diagnostic {
+    fn from(error: &'a Error) -> Diagnostic {
+        // the location is shown separately so it is left out of the message
+        let inner = error.inner();
+        let diagnostic = Diagnostic::new(inner.to_string(), error.span());
+
+        match help(inner) {
+            Some(help) => diagnostic.with_help(help),
+            None => diagnostic,
+        }
+    }
+}
+
+// suggest a fix for common mistakes
+fn help(error: &Error) -> Option<&'static str> {
+    match error {
+        Error::Ast(AstError::Expected(pattern)) if pattern == "}" => {
+            Some("every '{' needs a matching '}'")
+        }
+        Error::Ast(AstError::Expected(pattern)) if pattern == "]" => {
+            Some("every '[' needs a matching ']'")
+        }
+        Error::Ast(AstError::Expected(pattern)) if pattern == "block" => {
+            Some("functions are created from blocks, e.g. `{ 1 2 + } fn`")
+        }
+        Error::NotEnoughArguments(..) => {
+            Some("operators take their operands from the values before them, e.g. `1 2 +`")
+        }
+        Error::Interpreter(InterpreterError::VariableUndefined(_)) => {
+            Some("variables must be assigned before they are used, e.g. `x 5 =`")
+        }
+        Error::Interpreter(InterpreterError::NotCallable(_)) => {
+            Some("only functions can be called, create one with `{ ... } fn`")
+        }
+        Error::Interpreter(InterpreterError::AssignToConstant(_)) => {
+            Some("constants and builtins can't be reassigned, so choose a different name")
+        }
+        Error::Interpreter(InterpreterError::ArityMismatch(..)) => {
+            Some("arguments are the values before the call, e.g. `1 2 sub call`")
+        }
+        Error::Interpreter(InterpreterError::StackUnderflow) => {
+            Some("this needs more values on the stack than there are, e.g. `swap` needs two")
+        }
+        Error::Interpreter(InterpreterError::IndexOutOfRange(..)) => {
+            Some("indexes count up from 0, so the last item is at the length minus 1")
+        }
+        Error::Interpreter(InterpreterError::TypeMismatch { op: "+", .. }) => {
+            Some("`+` adds two numbers or joins two strings")
+        }
+        Error::Interpreter(InterpreterError::TypeMismatch { op, .. })
+            if *op == "and" || *op == "or" =>
+        {
+            Some("boolean operators need two booleans, e.g. `x 0 > x 10 < and`")
+        }
+        Error::Interpreter(InterpreterError::TypeMismatch { op, .. })
+            if op.contains(['<', '>']) =>
+        {
+            Some("only two numbers or two strings can be compared")
+        }
+        Error::Interpreter(InterpreterError::TypeMismatch { .. }) => {
+            Some("arithmetic operators need two numbers, e.g. `7 2 -`")
+        }
+        Error::Interpreter(InterpreterError::InvalidNumber(..)) => {
+            Some("results which are NaN or too large for a float can't be used")
+        }
+        Error::Interpreter(InterpreterError::SelfContaining(_)) => {
+            Some("add a copy instead, e.g. `xs xs [ ] concat call push call`")
+        }
+        Error::Interpreter(InterpreterError::UnhashableKey(_)) => {
+            Some("map keys must be numbers, booleans or strings")
+        }
+        Error::Interpreter(InterpreterError::ExpectedType("int", "float")) => {
+            Some("convert a float to an int with `int call`, e.g. `3.0 int call`")
+        }
+        Error::Interpreter(InterpreterError::ExpectedType("boolean", _)) => {
+            Some("conditions must be `true` or `false`, e.g. `x 0 >`")
+        }
+        Error::Format(FormatError::Unclosed) | Error::Format(FormatError::UnmatchedBrace) => {
+            Some("write a brace which isn't part of a placeholder twice, e.g. `{{`")
+        }
+        Error::Format(FormatError::NamedField(_)) => {
+            Some("`format` fills its placeholders in order, use `f\"{x}\"` to name a variable")
+        }
+        Error::Format(FormatError::ExpectedName(_)) => {
+            Some("each placeholder names a variable, e.g. `f\"x = {x}\"`")
+        }
+        _ => None,
+    }
+}
+
+// find the indent and width (in displayed columns) of the underline for `span` on `line`
+fn underline(line: &str, span: Span) -> (usize, usize) {
+    let width = |c: char| if c == '\t' { TAB.len() } else { 1 };
+
+    let mut chars = line.chars();
+    let indent = chars.by_ref().take(span.col - 1).map(width).sum();
+
+    // `span.end` is a byte offset into the whole program, so walk the line from `span.start`
+    let mut len = 0;
+    let mut underlined = 0;
+    for c in chars {
+        if len >= span.end - span.start {
+            break;
+        }
+        len += c.len_utf8();
+        underlined += width(c);
+    }
+
+    // always draw at least one caret, e.g. for errors at the end of the input
+    (indent, underlined.max(1))
+}
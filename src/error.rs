@@ -1,11 +1,15 @@
 //! Error handling types and convenience methods
 
 use std::fmt;
+use std::io;
 use std::num::ParseFloatError;
+use std::rc::Rc;
 use std::str::Utf8Error;
 
 use crate::ast::AstError;
 use crate::eval::InterpreterError;
+use crate::format::FormatError;
+use crate::span::Span;
 use crate::token::TokenError;
 
 /// The crate-wide error type
@@ -16,9 +20,40 @@ pub enum Error {
     Token(TokenError),
     Ast(AstError),
     Interpreter(InterpreterError),
+    Format(FormatError),
+    /// Reading or writing failed. `io::Error` can't be cloned, so it is shared.
+    Io(Rc<io::Error>),
     Utf8(Utf8Error),
     ParseFloat(ParseFloatError),
     NotEnoughArguments(usize, usize),
+    /// Any of the above errors, tagged with the location in the program where it occurred
+    Located(Span, Box<Error>),
+}
+
+impl Error {
+    /// Attach a location to the error, unless it already has a (more precise) one
+    pub fn at(self, span: Span) -> Error {
+        match self {
+            Error::Located(..) => self,
+            e => Error::Located(span, Box::new(e)),
+        }
+    }
+
+    /// The location in the program where the error occurred, if it is known
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::Located(span, _) => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// The error with any location information stripped
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::Located(_, e) => e.inner(),
+            e => e,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -27,11 +62,14 @@ impl fmt::Display for Error {
             Error::Token(e) => fmt::Display::fmt(e, f),
             Error::Ast(e) => fmt::Display::fmt(e, f),
             Error::Interpreter(e) => fmt::Display::fmt(e, f),
+            Error::Format(e) => fmt::Display::fmt(e, f),
+            Error::Io(e) => fmt::Display::fmt(e, f),
             Error::Utf8(e) => fmt::Display::fmt(e, f),
             Error::ParseFloat(e) => fmt::Display::fmt(e, f),
             Error::NotEnoughArguments(expected, got) => {
                 write!(f, "expected {} arguments but got {}", expected, got)
             }
+            Error::Located(span, e) => write!(f, "{}: {}", span, e),
         }
     }
 }
@@ -54,6 +92,18 @@ impl From<InterpreterError> for Error {
     }
 }
 
+impl From<FormatError> for Error {
+    fn from(error: FormatError) -> Self {
+        Error::Format(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(Rc::new(error))
+    }
+}
+
 impl From<Utf8Error> for Error {
     fn from(error: Utf8Error) -> Self {
         Error::Utf8(error)
@@ -1,130 +1,740 @@
-//! Evaluate an [`Ast`](../ast/enum.Ast.html) to produce values and console output
-//!
-//! TODO: add support for constants (e.g. PI 3.14 const =)
-//! TODO: create Const(Ast) `Ast` variant
-//! TODO: change `Context.vars` value type to (Ast, is_const: bool)
-//! TODO: disallow assignment where is_const is true
+//! Evaluate an [`Ast`](../ast/struct.Ast.html) to produce values and console output
 
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::mem;
 use std::ops;
+use std::rc::Rc;
 
-use crate::ast::{Ast, Parser};
 use crate::ast::Literal;
-use crate::error::Result;
+use crate::ast::{Ast, AstKind, BinaryOp, Parser, Segment, StackOp, UnaryOp};
+use crate::error::{Error, Result};
+use crate::map::Map;
+use crate::num::{BigInt, Rational};
+use crate::stdlib;
 
-/// A frothy evaluation context (variables)
+// a variable's value and whether it may be reassigned
 #[derive(Debug)]
+struct Var {
+    value: Value,
+    constant: bool,
+}
+
+// a single frame of variables, and the frame it is nested in
+#[derive(Debug, Default)]
+struct Scope {
+    vars: HashMap<String, Var>,
+    parent: Option<Rc<RefCell<Scope>>>,
+}
+
+/// A frothy evaluation context (variables)
+///
+/// Variables live in a chain of scopes: the global scope, a scope for each function call
+/// and a scope for each block. Lookups walk outwards from the innermost scope, and
+/// assignments define variables in the innermost scope unless `update` is used.
+///
+/// Constants can't be reassigned or redefined in the scope which defines them, but may be
+/// shadowed by a nested scope, e.g. by a function parameter of the same name.
+#[derive(Debug, Clone, Default)]
 pub struct Context {
-    vars: HashMap<String, Value>,
+    scope: Rc<RefCell<Scope>>,
 }
 
 impl Context {
+    /// Create a new global scope
     pub fn new() -> Context {
+        Context::default()
+    }
+
+    /// Create a new scope nested inside this one
+    pub fn child(&self) -> Context {
         Context {
-            vars: HashMap::new(),
+            scope: Rc::new(RefCell::new(Scope {
+                vars: HashMap::new(),
+                parent: Some(self.scope.clone()),
+            })),
+        }
+    }
+
+    // find the innermost scope which defines `ident`
+    fn find(&self, ident: &str) -> Option<Rc<RefCell<Scope>>> {
+        let mut scope = Some(self.scope.clone());
+
+        while let Some(current) = scope {
+            if current.borrow().vars.contains_key(ident) {
+                return Some(current);
+            }
+            scope = current.borrow().parent.clone();
+        }
+
+        None
+    }
+
+    // fail if `ident` is a constant in `scope`
+    fn check_not_constant(scope: &RefCell<Scope>, ident: &str) -> Result<()> {
+        match scope.borrow().vars.get(ident) {
+            Some(var) if var.constant => {
+                Err(InterpreterError::AssignToConstant(ident.to_string()).into())
+            }
+            _ => Ok(()),
         }
     }
 
     pub fn lookup<T: Into<String>>(&self, ident: T) -> Result<Value> {
         let ident = ident.into();
         // TODO figure out how variable values should be referenced. At the moment we just clone
-        self.vars
-            .get(&ident)
-            .cloned()
-            .ok_or_else(|| InterpreterError::VariableUndefined(ident).into())
+        match self.find(&ident) {
+            Some(scope) => Ok(scope.borrow().vars[&ident].value.clone()),
+            None => Err(InterpreterError::VariableUndefined(ident).into()),
+        }
     }
 
+    /// Register a builtin function as a constant
     pub fn builtin_func<T: Into<String>>(&mut self, name: T, f: BuiltinFn) {
         let name = name.into();
-        self.set(name.clone(), Value::BuiltinFunc(name, f));
+        self.builtin_const(name.clone(), Value::BuiltinFunc(name, f));
+    }
+
+    /// Register a builtin constant, such as `PI`
+    pub fn builtin_const<T: Into<String>>(&mut self, name: T, value: Value) {
+        let var = Var {
+            value,
+            constant: true,
+        };
+        self.scope.borrow_mut().vars.insert(name.into(), var);
+    }
+
+    /// Assign to a variable in the innermost scope, shadowing any outer variable
+    pub fn set<T: Into<String>>(&mut self, ident: T, value: Value) -> Result<()> {
+        self.define(ident.into(), value, false)
     }
 
-    pub fn set<T: Into<String>>(&mut self, ident: T, value: Value) {
-        self.vars.insert(ident.into(), value);
+    /// Define a constant in the innermost scope
+    pub fn set_const<T: Into<String>>(&mut self, ident: T, value: Value) -> Result<()> {
+        self.define(ident.into(), value, true)
+    }
+
+    // only a constant in the innermost scope stops a definition, outer ones are shadowed
+    fn define(&mut self, ident: String, value: Value, constant: bool) -> Result<()> {
+        Context::check_not_constant(&self.scope, &ident)?;
+        let var = Var { value, constant };
+        self.scope.borrow_mut().vars.insert(ident, var);
+        Ok(())
+    }
+
+    /// Assign to the variable in the nearest scope which defines it
+    pub fn update<T: Into<String>>(&mut self, ident: T, value: Value) -> Result<()> {
+        self.replace(ident.into(), value, false)
+    }
+
+    /// Assign to the variable in the nearest scope which defines it, making it a constant
+    pub fn update_const<T: Into<String>>(&mut self, ident: T, value: Value) -> Result<()> {
+        self.replace(ident.into(), value, true)
+    }
+
+    fn replace(&mut self, ident: String, value: Value, constant: bool) -> Result<()> {
+        match self.find(&ident) {
+            Some(scope) => {
+                Context::check_not_constant(&scope, &ident)?;
+                let var = Var { value, constant };
+                scope.borrow_mut().vars.insert(ident, var);
+                Ok(())
+            }
+            None => Err(InterpreterError::VariableUndefined(ident).into()),
+        }
     }
 }
 
 /// A frothy interpreter
 ///
-/// Builtins are registered when the interpreter is created
-#[derive(Debug)]
+/// Builtins are registered when the interpreter is created. Evaluating an `Ast` pushes its
+/// result(s) onto the interpreter's stack, where they can be consumed by function calls.
 pub struct Interpreter {
     ctx: Context,
+    stack: Vec<Value>,
+    // whether operators give `Nil` rather than an error for operands of the wrong types
+    lenient_types: bool,
+    numeric_policy: NumericPolicy,
+    output: Box<dyn Write>,
+    input: Box<dyn BufRead>,
+}
+
+impl fmt::Debug for Interpreter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Interpreter")
+            .field("ctx", &self.ctx)
+            .field("stack", &self.stack)
+            .field("lenient_types", &self.lenient_types)
+            .field("numeric_policy", &self.numeric_policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Interpreter {
+        Interpreter::new()
+    }
 }
 
 impl Interpreter {
     /// Create a new frothy interpreter and register builtins
     pub fn new() -> Interpreter {
-        // set up builtins
+        // builtins are constants in the global scope, so only a nested scope can hide them
         let mut ctx = Context::new();
+        stdlib::register(&mut ctx);
+
+        Interpreter {
+            ctx,
+            stack: vec![],
+            lenient_types: false,
+            numeric_policy: NumericPolicy::default(),
+            output: Box::new(io::stdout()),
+            input: Box::new(BufReader::new(io::stdin())),
+        }
+    }
+
+    /// Send everything the program prints to `output` rather than stdout, e.g. a
+    /// [`Capture`](../io/struct.Capture.html)
+    pub fn with_output<W: Write + 'static>(mut self, output: W) -> Interpreter {
+        self.output = Box::new(output);
+        self
+    }
+
+    /// Where the program's output is written
+    pub fn output(&mut self) -> &mut dyn Write {
+        &mut *self.output
+    }
+
+    /// Read the program's input from `input` rather than stdin
+    pub fn with_input<R: BufRead + 'static>(mut self, input: R) -> Interpreter {
+        self.input = Box::new(input);
+        self
+    }
+
+    /// Where the program's input is read from
+    pub fn input(&mut self) -> &mut dyn BufRead {
+        &mut *self.input
+    }
+
+    /// Make operators give `Nil` for operands of the wrong types rather than an error, as they
+    /// did in earlier versions of frothy
+    pub fn with_lenient_types(mut self, lenient: bool) -> Interpreter {
+        self.lenient_types = lenient;
+        self
+    }
 
-        // print function
-        ctx.builtin_func("print", |ctx| {
-            println!("{}", ctx.lookup("print_arg")?);
-            Ok(Value::Nil)
-        });
+    /// Choose how division and math builtins handle results which aren't finite numbers
+    pub fn with_numeric_policy(mut self, policy: NumericPolicy) -> Interpreter {
+        self.numeric_policy = policy;
+        self
+    }
 
-        // pi constant
-        ctx.set("PI", Value::Number(::std::f64::consts::PI));
+    pub fn numeric_policy(&self) -> NumericPolicy {
+        self.numeric_policy
+    }
 
-        Interpreter { ctx }
+    // evaluate an `Ast`, tagging any error which doesn't already have a location with its span
+    fn eval(&mut self, ast: &Ast) -> Result<()> {
+        self.eval_kind(&ast.kind).map_err(|e| e.at(ast.span))
     }
 
-    fn eval(&mut self, ast: &Ast) -> Result<Value> {
-        match ast {
-            Ast::Literal(lit) => Ok(lit.clone().into()),
-            Ast::Add(a, b) => Ok(self.eval(a)? + self.eval(b)?),
-            Ast::Subtract(a, b) => Ok(self.eval(a)? - self.eval(b)?),
-            Ast::Multiply(a, b) => Ok(self.eval(a)? * self.eval(b)?),
-            Ast::Divide(a, b) => Ok(self.eval(a)? / self.eval(b)?),
-            // assignment returns `Nil`
-            Ast::Assign(ident, ast) => {
-                let value = self.eval(ast)?;
-                self.ctx.set(ident.clone(), value);
-                Ok(Value::Nil)
+    fn eval_kind(&mut self, kind: &AstKind) -> Result<()> {
+        match kind {
+            AstKind::Literal(lit) => self.stack.push(lit.clone().into()),
+            AstKind::Binary(op, a, b) => {
+                self.eval(a)?;
+                self.eval(b)?;
+                let rhs = self.pop()?;
+                let lhs = self.pop()?;
+                let value = self.lenient(binary(*op, lhs, rhs, self.numeric_policy))?;
+                self.stack.push(value);
+            }
+            AstKind::Unary(UnaryOp::Not, a) => {
+                let value = self.eval_value(a)?;
+                let value = self.lenient(!value)?;
+                self.stack.push(value);
+            }
+            // assignment consumes its value and pushes nothing
+            AstKind::Assign(ident, ast) => match &ast.kind {
+                AstKind::Const(ast) => {
+                    let value = self.eval_value(ast)?;
+                    self.ctx.set_const(ident.as_str(), value)?;
+                }
+                _ => {
+                    let value = self.eval_value(ast)?;
+                    self.ctx.set(ident.as_str(), value)?;
+                }
+            },
+            AstKind::Reassign(ident, ast) => match &ast.kind {
+                AstKind::Const(ast) => {
+                    let value = self.eval_value(ast)?;
+                    self.ctx.update_const(ident.as_str(), value)?;
+                }
+                _ => {
+                    let value = self.eval_value(ast)?;
+                    self.ctx.update(ident.as_str(), value)?;
+                }
+            },
+            // `const` only has an effect on the value of an assignment
+            AstKind::Const(ast) => self.eval(ast)?,
+            // a block has its own scope, and leaves the results of each of its `Ast`s on the stack
+            AstKind::Block(asts) => {
+                self.scoped(self.ctx.child(), |interp| interp.eval_block(asts))?
+            }
+
+            // a list holds every value left by its items, which are evaluated in the current scope
+            AstKind::List(items) => {
+                let start = self.stack.len();
+                self.eval_block(items)?;
+                let values = self.stack.split_off(start.min(self.stack.len()));
+                self.push(Value::List(Rc::new(RefCell::new(values))));
+            }
+            // a map is made from alternating keys and values, like a list
+            AstKind::Map(items) => {
+                let start = self.stack.len();
+                self.eval_block(items)?;
+                let mut values = self
+                    .stack
+                    .split_off(start.min(self.stack.len()))
+                    .into_iter();
+
+                let mut map = Map::new();
+                while let Some(key) = values.next() {
+                    match values.next() {
+                        Some(value) => map.insert(key, value)?,
+                        None => return Err(InterpreterError::UnpairedKey(key.to_string()).into()),
+                    };
+                }
+                self.push(Value::Map(Rc::new(RefCell::new(map))));
+            }
+            AstKind::Interpolate(segments) => {
+                let mut s = String::new();
+                for segment in segments {
+                    match segment {
+                        Segment::Text(text) => s.push_str(text),
+                        Segment::Field(ast, spec) => {
+                            s.push_str(&spec.apply(&self.eval_value(ast)?))
+                        }
+                    }
+                }
+                self.push(Value::String(s));
+            }
+
+            // functions capture the scope they are created in. a function stored in the scope
+            // it captures forms a reference cycle, so it lives until the program ends
+            AstKind::Func(params, body) => self.stack.push(Value::Func(Rc::new(Function {
+                params: params.clone(),
+                body: body.clone(),
+                env: self.ctx.clone(),
+            }))),
+            AstKind::Call(ast) => {
+                let value = self.eval_value(ast)?;
+                self.call(&value)?;
+            }
+            // `fn` always removes parameters from its block, and only `fn` may follow them
+            AstKind::Params(_) => unreachable!("parameters outside of a function"),
+            // only the chosen branch is evaluated
+            AstKind::If(cond, then) => {
+                if self.eval_condition(cond)? {
+                    self.eval(then)?;
+                }
+            }
+            AstKind::IfElse(cond, then, otherwise) => {
+                if self.eval_condition(cond)? {
+                    self.eval(then)?;
+                } else {
+                    self.eval(otherwise)?;
+                }
+            }
+            AstKind::While(cond, body) => {
+                while self.eval_condition(cond)? && self.eval_loop_body(body)? {}
             }
-            // Block returns the result of the last `Ast` to execute successfully
-            Ast::Block(asts) => self.eval_block(asts),
+            AstKind::Times(count, body) => {
+                let count = self.eval_int(count)?;
+                let mut i = 0;
+                while i < count && self.eval_loop_body(body)? {
+                    i += 1;
+                }
+            }
+            // the index counts up from `start` to (but not including) `end`, and is only
+            // defined inside the loop. each iteration binds it in a new scope, so a function
+            // created in the body keeps the index of its own iteration
+            AstKind::For(ident, start, end, body) => {
+                let start = self.eval_int(start)?;
+                let end = self.eval_int(end)?;
+                let mut i = start;
+                while i < end {
+                    let mut ctx = self.ctx.child();
+                    ctx.set(ident.as_str(), Value::Int(i))?;
+                    if !self.scoped(ctx, |interp| interp.eval_loop_body(body))? {
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            AstKind::Stack(op) => self.eval_stack_op(*op)?,
+            // the operand was pushed before its operator was evaluated, so leave it there
+            AstKind::FromStack => {}
+            // `break` and `continue` unwind to the innermost loop as errors
+            AstKind::Break => return Err(InterpreterError::Break.into()),
+            AstKind::Continue => return Err(InterpreterError::Continue.into()),
+            AstKind::Ident(ident) => {
+                let value = self.ctx.lookup(ident.as_str())?;
+                self.stack.push(value);
+            }
+        }
 
-            Ast::Func(asts) => Ok(Value::Func(asts.clone())),
-            Ast::Call(ast) => {
-                let value = self.eval(ast)?;
-                self.call(&value)
+        Ok(())
+    }
+
+    // in lenient mode, replace the result of an operation on the wrong types with `Nil`
+    fn lenient(&self, result: Result<Value>) -> Result<Value> {
+        match result {
+            Err(e) if self.lenient_types => match e.inner() {
+                Error::Interpreter(InterpreterError::TypeMismatch { .. })
+                | Error::Interpreter(InterpreterError::ExpectedType(..)) => Ok(Value::Nil),
+                _ => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    // run `f` with `ctx` as the current context, restoring the current context afterwards
+    fn scoped<T, F>(&mut self, ctx: Context, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Interpreter) -> Result<T>,
+    {
+        let outer = mem::replace(&mut self.ctx, ctx);
+        let result = f(self);
+        self.ctx = outer;
+        result
+    }
+
+    // evaluate an operand and take its result from the stack
+    fn eval_value(&mut self, ast: &Ast) -> Result<Value> {
+        self.eval(ast)?;
+        self.pop().map_err(|e| e.at(ast.span))
+    }
+
+    /// The values on the stack, from the bottom up
+    pub fn stack(&self) -> &[Value] {
+        &self.stack
+    }
+
+    /// Push a value to the stack
+    pub fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    /// Pop a value from the stack
+    pub fn pop(&mut self) -> Result<Value> {
+        self.stack
+            .pop()
+            .ok_or_else(|| InterpreterError::StackUnderflow.into())
+    }
+
+    // get a copy of the value `depth` values below the top of the stack
+    fn peek(&self, depth: usize) -> Result<Value> {
+        self.stack
+            .len()
+            .checked_sub(depth + 1)
+            .map(|i| self.stack[i].clone())
+            .ok_or_else(|| InterpreterError::StackUnderflow.into())
+    }
+
+    // rearrange the values on top of the stack
+    fn eval_stack_op(&mut self, op: StackOp) -> Result<()> {
+        match op {
+            StackOp::Dup => {
+                let a = self.peek(0)?;
+                self.push(a);
+            }
+            StackOp::Drop => {
+                self.pop()?;
+            }
+            StackOp::Swap => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(b);
+                self.push(a);
+            }
+            StackOp::Over => {
+                let a = self.peek(1)?;
+                self.push(a);
+            }
+            StackOp::Rot => {
+                let c = self.pop()?;
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(b);
+                self.push(c);
+                self.push(a);
+            }
+            StackOp::Nip => {
+                let b = self.pop()?;
+                self.pop()?;
+                self.push(b);
+            }
+            StackOp::Tuck => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(b.clone());
+                self.push(a);
+                self.push(b);
+            }
+            StackOp::Depth => {
+                let depth = self.stack.len() as i64;
+                self.push(Value::Int(depth));
             }
-            Ast::Ident(ident) => self.ctx.lookup(ident),
+            StackOp::Clear => self.stack.clear(),
+        }
+
+        Ok(())
+    }
+
+    // evaluate the body of a loop and return whether the loop should keep running
+    fn eval_loop_body(&mut self, body: &Ast) -> Result<bool> {
+        match self.eval(body) {
+            Ok(_) => Ok(true),
+            Err(e) => match e.inner() {
+                Error::Interpreter(InterpreterError::Break) => Ok(false),
+                Error::Interpreter(InterpreterError::Continue) => Ok(true),
+                _ => Err(e),
+            },
+        }
+    }
+
+    // evaluate an operand which must be an int, e.g. the count of a `times` loop
+    fn eval_int(&mut self, ast: &Ast) -> Result<i64> {
+        match self.eval_value(ast)? {
+            Value::Int(n) => Ok(n),
+            value => Err(
+                Error::from(InterpreterError::ExpectedType("int", value.type_name())).at(ast.span),
+            ),
+        }
+    }
+
+    // evaluate the condition of a control flow expression, which must be a boolean
+    fn eval_condition(&mut self, cond: &Ast) -> Result<bool> {
+        match self.eval_value(cond)? {
+            Value::Boolean(b) => Ok(b),
+            value => Err(
+                Error::from(InterpreterError::ExpectedType("boolean", value.type_name()))
+                    .at(cond.span),
+            ),
         }
     }
 
+    /// Run a program and return the values left on the stack
     pub fn interpret(mut self, program: &str) -> Result<Vec<Value>> {
+        self.run(program)
+    }
+
+    /// Run a program, keeping any variables it defines, and take the values it left on the stack
+    ///
+    /// If the program fails then the values it left are dropped, so the next run starts with an
+    /// empty stack.
+    pub fn run(&mut self, program: &str) -> Result<Vec<Value>> {
+        let result = self.run_program(program);
+        let values = self.stack.drain(..).collect();
+        result.map(|_| values)
+    }
+
+    fn run_program(&mut self, program: &str) -> Result<()> {
         let parser = Parser::new(program);
-        parser.parse()?.iter().map(|ast| self.eval(ast)).collect()
+        for ast in parser.parse()? {
+            self.eval(&ast)?;
+        }
+        Ok(())
+    }
+
+    /// Call the function bound to `name` with `args` and return every value it leaves
+    ///
+    /// The function runs on a stack holding only `args`, so it can't see or consume any values
+    /// already on the interpreter's stack.
+    pub fn call_function<T: Into<String>>(
+        &mut self,
+        name: T,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        let func = self.ctx.lookup(name)?;
+        self.call_with(&func, args)
     }
 
-    fn eval_block(&mut self, asts: &[Ast]) -> Result<Value> {
-        let mut value = Value::Nil;
+    /// Call `func` with `args` on a stack of its own, and return every value it leaves
+    pub fn call_with(&mut self, func: &Value, args: Vec<Value>) -> Result<Vec<Value>> {
+        let stack = mem::replace(&mut self.stack, args);
+        let result = self.call(func);
+        let results = mem::replace(&mut self.stack, stack);
+
+        result.map(|_| results)
+    }
 
+    fn eval_block(&mut self, asts: &[Ast]) -> Result<()> {
         for ast in asts {
-            value = self.eval(ast)?;
+            self.eval(ast)?;
         }
 
-        Ok(value)
+        Ok(())
     }
 
-    fn call(&mut self, value: &Value) -> Result<Value> {
+    /// Call a function with the values on the stack, leaving all of its results on the stack
+    pub fn call(&mut self, value: &Value) -> Result<()> {
         match value {
-            Value::Func(asts) => self.eval_block(asts),
-            Value::BuiltinFunc(_, f) => f(&self.ctx),
+            Value::Func(func) => {
+                // the arguments are the values on top of the stack, in the order they were pushed
+                let arity = func.params.len();
+                if self.stack.len() < arity {
+                    return Err(InterpreterError::ArityMismatch(arity, self.stack.len()).into());
+                }
+                let args = self.stack.split_off(self.stack.len() - arity);
+
+                // bind the arguments in a new scope inside the scope the function was created in
+                let result = self.scoped(func.env.child(), |interp| {
+                    for (param, arg) in func.params.iter().zip(args) {
+                        interp.ctx.set(param.as_str(), arg)?;
+                    }
+                    interp.eval_block(&func.body)
+                });
+                result.map_err(outside_loop)
+            }
+            Value::BuiltinFunc(_, f) => f(self),
             _ => Err(InterpreterError::NotCallable(format!("{}", value)).into()),
         }
     }
 }
 
-/// Errors encountered while interpreting an [`Ast`](../ast/enum.Ast.html)
+// `break` and `continue` can't leave the function they are in, so one which escapes a function
+// body becomes an error which no loop catches
+fn outside_loop(e: Error) -> Error {
+    let word = match e.inner() {
+        Error::Interpreter(InterpreterError::Break) => "break",
+        Error::Interpreter(InterpreterError::Continue) => "continue",
+        _ => return e,
+    };
+    let error = Error::from(InterpreterError::OutsideLoop(word));
+    match e.span() {
+        Some(span) => error.at(span),
+        None => error,
+    }
+}
+
+// apply a binary operator to its evaluated operands
+fn binary(op: BinaryOp, lhs: Value, rhs: Value, policy: NumericPolicy) -> Result<Value> {
+    // only numbers and strings can be ordered
+    let compare = |f: fn(Ordering) -> bool, name: &'static str| match lhs.compare(&rhs) {
+        Some(ordering) => Ok(Value::Boolean(f(ordering))),
+        None => Err(mismatch(name, &lhs, &rhs)),
+    };
+
+    match op {
+        BinaryOp::Add => lhs + rhs,
+        BinaryOp::Subtract => lhs - rhs,
+        BinaryOp::Multiply => lhs * rhs,
+        BinaryOp::Divide => policy.divide("/", lhs, rhs, ops::Div::div),
+        BinaryOp::IntDivide => policy.divide("//", lhs, rhs, Value::int_div),
+        BinaryOp::Modulo => policy.divide("%", lhs, rhs, ops::Rem::rem),
+        BinaryOp::Equals => Ok(Value::Boolean(lhs == rhs)),
+        BinaryOp::NotEquals => Ok(Value::Boolean(lhs != rhs)),
+        BinaryOp::Less => compare(|o| o == Ordering::Less, "<"),
+        BinaryOp::LessEqual => compare(|o| o != Ordering::Greater, "<="),
+        BinaryOp::Greater => compare(|o| o == Ordering::Greater, ">"),
+        BinaryOp::GreaterEqual => compare(|o| o != Ordering::Less, ">="),
+        BinaryOp::And => lhs & rhs,
+        BinaryOp::Or => lhs | rhs,
+    }
+}
+
+/// How division and math builtins handle results which aren't finite numbers
+///
+/// Dividing an int or rational by zero is always an error, since they have no infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericPolicy {
+    /// Dividing a float by zero is an error, as is any result which is NaN or an infinity
+    /// that wasn't given one, e.g. `1e308 0.1 /`
+    #[default]
+    Strict,
+    /// Follow IEEE 754, so `1 0.0 /` gives `inf` and `0.0 0.0 /` gives `NaN`
+    Ieee,
+}
+
+impl NumericPolicy {
+    /// Check `result`, the outcome of applying `op` to `args`. In strict mode NaN is an error,
+    /// and so is an infinity unless one of `args` was already infinite or NaN.
+    pub fn check(self, op: &'static str, args: &[f64], result: f64) -> Result<f64> {
+        let invalid = result.is_nan() || result.is_infinite() && args.iter().all(|n| n.is_finite());
+        if self == NumericPolicy::Strict && invalid {
+            return Err(InterpreterError::InvalidNumber(op, result).into());
+        }
+        Ok(result)
+    }
+
+    // apply a division operator, checking for a float divisor of zero and the result
+    fn divide(
+        self,
+        op: &'static str,
+        lhs: Value,
+        rhs: Value,
+        f: fn(Value, Value) -> Result<Value>,
+    ) -> Result<Value> {
+        let args = [lhs.as_float(), rhs.as_float()];
+        if self == NumericPolicy::Strict && lhs.is_number() && args[1] == Some(0.0) {
+            return Err(InterpreterError::DivisionByZero.into());
+        }
+        match (f(lhs, rhs)?, args) {
+            (Value::Float(n), [Some(a), Some(b)]) => self.check(op, &[a, b], n).map(Value::Float),
+            (value, _) => Ok(value),
+        }
+    }
+}
+
+/// Errors encountered while interpreting an [`Ast`](../ast/struct.Ast.html)
 #[derive(Debug, Clone)]
 pub enum InterpreterError {
     VariableUndefined(String),
     NotCallable(String),
+    /// A value of the wrong type was used, e.g. a number as the condition of an `if`
+    ExpectedType(&'static str, &'static str),
+    /// A constant was assigned to or redefined in its own scope
+    AssignToConstant(String),
+    /// A value was needed but the stack was empty
+    StackUnderflow,
+    /// A function was called with fewer values on the stack than it has parameters
+    ArityMismatch(usize, usize),
+    /// An index which is not within the bounds of a list or string of the given length
+    IndexOutOfRange(i64, usize),
+    /// A number was divided by zero. Floats are only checked by the strict numeric policy.
+    DivisionByZero,
+    /// An operation gave NaN or an infinity under the strict numeric policy
+    InvalidNumber(&'static str, f64),
+    /// A float which is not a whole number in the range of an int, e.g. NaN
+    NotAnInt(f64),
+    /// An operator was applied to operands of types it doesn't support, e.g. `true 1 +`
+    TypeMismatch {
+        op: &'static str,
+        lhs_type: &'static str,
+        rhs_type: &'static str,
+    },
+    /// Two values which can't be ordered, e.g. when sorting a list
+    Incomparable(&'static str, &'static str),
+    /// A result which would be too large to build, e.g. a string repeated too many times
+    TooLarge(&'static str),
+    /// A map key of a type which can't be used as one, e.g. a list
+    UnhashableKey(&'static str),
+    /// A list or map which would hold itself, e.g. `xs xs push call`
+    SelfContaining(&'static str),
+    /// A map literal with an odd number of items, so the last key has no value
+    UnpairedKey(String),
+    /// Raised by `break` and caught by the innermost loop
+    Break,
+    /// Raised by `continue` and caught by the innermost loop
+    Continue,
+    /// A `break` or `continue` which tried to leave the function it is in
+    OutsideLoop(&'static str),
 }
 
 impl fmt::Display for InterpreterError {
@@ -136,42 +746,262 @@ impl fmt::Display for InterpreterError {
             InterpreterError::NotCallable(displayed) => {
                 write!(f, "value '{}' is not callable", displayed)
             }
+            InterpreterError::ExpectedType(expected, got) => {
+                write!(f, "expected {} but got {}", expected, got)
+            }
+            InterpreterError::AssignToConstant(ident) => {
+                write!(f, "cannot assign to constant '{}'", ident)
+            }
+            InterpreterError::StackUnderflow => f.write_str("stack underflow"),
+            InterpreterError::ArityMismatch(expected, got) => {
+                write!(f, "function expects {} arguments but got {}", expected, got)
+            }
+            InterpreterError::IndexOutOfRange(index, len) => {
+                write!(f, "index {} is out of range for length {}", index, len)
+            }
+            InterpreterError::TypeMismatch {
+                op,
+                lhs_type,
+                rhs_type,
+            } => write!(f, "cannot apply '{}' to {} and {}", op, lhs_type, rhs_type),
+            InterpreterError::Incomparable(lhs, rhs) => {
+                write!(f, "cannot compare {} with {}", lhs, rhs)
+            }
+            InterpreterError::DivisionByZero => f.write_str("division by zero"),
+            InterpreterError::InvalidNumber(op, n) => write!(f, "'{}' gave {:?}", op, n),
+            InterpreterError::NotAnInt(n) => write!(f, "{:?} can't be converted to an int", n),
+            InterpreterError::TooLarge(type_name) => {
+                write!(f, "the resulting {} would be too large", type_name)
+            }
+            InterpreterError::UnhashableKey(type_name) => {
+                write!(f, "a {} can't be used as a map key", type_name)
+            }
+            InterpreterError::SelfContaining(type_name) => {
+                write!(f, "a {} can't contain itself", type_name)
+            }
+            InterpreterError::UnpairedKey(key) => write!(f, "map key '{}' has no value", key),
+            // these are only seen if they escape every loop
+            InterpreterError::Break => f.write_str("'break' outside of a loop"),
+            InterpreterError::Continue => f.write_str("'continue' outside of a loop"),
+            InterpreterError::OutsideLoop(word) => write!(f, "'{}' outside of a loop", word),
         }
     }
 }
 
-/// A builtin function
-pub type BuiltinFn = fn(&Context) -> Result<Value>;
+/// A function defined in a frothy program, and the scope it was defined in
+pub struct Function {
+    pub params: Vec<String>,
+    pub body: Vec<Ast>,
+    pub env: Context,
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the environment is left out as it may contain the function itself
+        f.debug_struct("Function")
+            .field("params", &self.params)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// A builtin function, which takes its arguments from and pushes its results to the stack
+pub type BuiltinFn = fn(&mut Interpreter) -> Result<()>;
 
 /// A `frothy` value that can be used at runtime
 #[derive(Clone)]
 pub enum Value {
-    Number(f64),
+    Int(i64),
+    /// An integer which doesn't fit in an `i64`. Smaller integers are always `Int`s
+    BigInt(BigInt),
+    /// A fraction which is not a whole number. Whole numbers are always `Int`s or `BigInt`s
+    Rational(Rational),
+    Float(f64),
     Boolean(bool),
+    String(String),
     Nil,
-    Func(Vec<Ast>),
+    /// A list which is shared by every copy of the value, so changes to it are seen by all of them
+    List(Rc<RefCell<Vec<Value>>>),
+    /// A map which is shared in the same way as a list
+    Map(Rc<RefCell<Map>>),
+    Func(Rc<Function>),
     BuiltinFunc(String, BuiltinFn),
 }
 
 impl Value {
-    /// Determine if two values are equal to each other
-    pub fn eq(&self, other: &Self) -> Value {
-        // currently only values of the same `Value` variant can be compared
-        // they are compared as defined in the operator `impl`s section below
+    /// The name of the value's type, for use in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::BigInt(_) => "bigint",
+            Value::Rational(_) => "rational",
+            Value::Float(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Nil => "Nil",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Func(_) | Value::BuiltinFunc(..) => "function",
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.is_exact() || matches!(self, Value::Float(_))
+    }
+
+    /// Whether the value is a number which is stored exactly, i.e. an int or a rational
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::BigInt(_) | Value::Rational(_))
+    }
+
+    /// Convert a number to a float, or `None` if the value isn't a number
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Int(n) => Some(*n as f64),
+            Value::BigInt(n) => Some(n.to_f64()),
+            Value::Rational(n) => Some(n.to_f64()),
+            Value::Float(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Convert an exact number to a rational, or `None` if the value isn't an exact number
+    pub fn as_exact(&self) -> Option<Rational> {
+        match self {
+            Value::Int(n) => Some(Rational::from(*n)),
+            Value::BigInt(n) => Some(Rational::from(n.clone())),
+            Value::Rational(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    // the exact value of any number, including finite floats
+    fn exact_value(&self) -> Option<Rational> {
+        match self {
+            Value::Float(n) => Rational::from_f64(*n),
+            value => value.as_exact(),
+        }
+    }
+
+    /// Divide two numbers, rounding the result down to a whole number
+    pub fn int_div(self, rhs: Value) -> Result<Value> {
+        division(
+            "//",
+            self,
+            rhs,
+            |lhs, rhs| {
+                let quotient = lhs.checked_div(rhs)?;
+                // division truncates towards zero, so step down if the result was negative
+                if lhs % rhs != 0 && (lhs < 0) != (rhs < 0) {
+                    Some(quotient - 1)
+                } else {
+                    Some(quotient)
+                }
+            },
+            |lhs, rhs| Rational::from((lhs / rhs).floor()),
+            |lhs, rhs| (lhs / rhs).floor(),
+        )
+    }
+
+    /// Whether the value is the list or map `collection`, or holds it at any depth
+    pub fn holds(&self, collection: &Value) -> bool {
+        let same = match (self, collection) {
+            (Value::List(lhs), Value::List(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Map(lhs), Value::Map(rhs)) => Rc::ptr_eq(lhs, rhs),
+            _ => false,
+        };
+        // collections never hold themselves, so this ends. map keys can't be collections
+        same || match self {
+            Value::List(list) => list.borrow().iter().any(|item| item.holds(collection)),
+            Value::Map(map) => map.borrow().values().any(|value| value.holds(collection)),
+            _ => false,
+        }
+    }
+
+    /// The value as it is written in a program, so unlike `to_string` a string is quoted
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(s) => format!("{:?}", s),
+            value => value.to_string(),
+        }
+    }
+
+    /// Order two values, if they are of types which can be compared
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        // numbers of any type are ordered by their exact values and strings lexicographically
         match (self, other) {
-            (Value::Number(lhs), Value::Number(rhs)) => Value::Boolean(lhs == rhs),
-            (Value::Boolean(lhs), Value::Boolean(rhs)) => Value::Boolean(lhs == rhs),
-            _ => Value::Nil,
+            (Value::Int(lhs), Value::Int(rhs)) => Some(lhs.cmp(rhs)),
+            (lhs, rhs) if lhs.is_number() && rhs.is_number() => {
+                match (lhs.exact_value(), rhs.exact_value()) {
+                    (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+                    // infinities and NaN
+                    _ => lhs.as_float()?.partial_cmp(&rhs.as_float()?),
+                }
+            }
+            (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+            _ => None,
         }
     }
+}
 
-    /// Determine if two values are not equal to each other
-    pub fn neq(&self, other: &Self) -> Value {
-        // use the inverse of `Value::eq`
-        if let Value::Boolean(b) = self.eq(other) {
-            Value::Boolean(!b)
-        } else {
-            Value::Nil
+// equality and hashing impls
+
+// values of different types are never equal, except for numbers which are equal if they have
+// the same value. lists and maps are compared by their contents
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Int(lhs), Value::Int(rhs)) => lhs == rhs,
+            (Value::Float(lhs), Value::Float(rhs)) => lhs == rhs,
+            (lhs, rhs) if lhs.is_number() && rhs.is_number() => {
+                match (lhs.exact_value(), rhs.exact_value()) {
+                    (Some(lhs), Some(rhs)) => lhs == rhs,
+                    _ => false,
+                }
+            }
+            (Value::Boolean(lhs), Value::Boolean(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Nil, Value::Nil) => true,
+            (Value::List(lhs), Value::List(rhs)) => {
+                Rc::ptr_eq(lhs, rhs) || *lhs.borrow() == *rhs.borrow()
+            }
+            (Value::Map(lhs), Value::Map(rhs)) => {
+                Rc::ptr_eq(lhs, rhs) || *lhs.borrow() == *rhs.borrow()
+            }
+            (Value::Func(lhs), Value::Func(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::BuiltinFunc(lhs, _), Value::BuiltinFunc(rhs, _)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // equal numbers must hash the same, so they are hashed by their exact value, as an
+        // `i64` if it is one, and there is one NaN
+        if let Value::Int(n) = self {
+            return n.hash(state);
+        }
+        if self.is_number() {
+            return match self.exact_value() {
+                Some(n) => match n.numer().to_i64() {
+                    Some(int) if n.is_integer() => int.hash(state),
+                    _ => n.hash(state),
+                },
+                None => match self.as_float() {
+                    Some(n) if n.is_nan() => f64::NAN.to_bits().hash(state),
+                    n => n.map(f64::to_bits).hash(state),
+                },
+            };
+        }
+
+        mem::discriminant(self).hash(state);
+        match self {
+            Value::Boolean(b) => b.hash(state),
+            Value::String(s) => s.hash(state),
+            // collections can change, so only their type is hashed, and functions have nothing
+            // worth hashing
+            _ => {}
         }
     }
 }
@@ -181,21 +1011,63 @@ impl Value {
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Value::Number(n) => fmt::Display::fmt(n, f),
+            Value::Int(n) => fmt::Display::fmt(n, f),
+            Value::BigInt(n) => fmt::Display::fmt(n, f),
+            Value::Rational(n) => fmt::Display::fmt(n, f),
+            // floats always have a decimal point or exponent so they aren't read back as ints
+            Value::Float(n) => fmt::Debug::fmt(n, f),
             Value::Boolean(b) => fmt::Display::fmt(b, f),
+            Value::String(s) => f.write_str(s),
             Value::Nil => write!(f, "Nil"),
+            // items are written as they would be in a literal, so strings are quoted
+            Value::List(list) => {
+                f.write_str("[")?;
+                for (i, item) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    fmt_item(item, f)?;
+                }
+                f.write_str("]")
+            }
+            Value::Map(map) => {
+                f.write_str("%{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    fmt_item(key, f)?;
+                    f.write_str(" ")?;
+                    fmt_item(value, f)?;
+                }
+                f.write_str("}")
+            }
             Value::Func(_) => f.write_str("<fn>"),
             Value::BuiltinFunc(name, _) => write!(f, "<builtin-fn:{}>", name),
         }
     }
 }
 
+// write an item of a list or map
+fn fmt_item(item: &Value, f: &mut fmt::Formatter) -> fmt::Result {
+    match item {
+        Value::String(s) => write!(f, "{:?}", s),
+        item => write!(f, "{}", item),
+    }
+}
+
 impl fmt::Debug for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Value::Boolean(b) => f.debug_tuple("Boolean").field(b).finish(),
-            Value::Number(n) => f.debug_tuple("Number").field(n).finish(),
-            Value::Func(asts) => f.debug_tuple("Func").field(asts).finish(),
+            Value::Int(n) => f.debug_tuple("Int").field(n).finish(),
+            Value::BigInt(n) => f.debug_tuple("BigInt").field(n).finish(),
+            Value::Rational(n) => f.debug_tuple("Rational").field(n).finish(),
+            Value::Float(n) => f.debug_tuple("Float").field(n).finish(),
+            Value::String(s) => f.debug_tuple("String").field(s).finish(),
+            Value::List(list) => f.debug_tuple("List").field(&list.borrow()).finish(),
+            Value::Map(map) => f.debug_tuple("Map").field(&map.borrow()).finish(),
+            Value::Func(func) => f.debug_tuple("Func").field(func).finish(),
             Value::BuiltinFunc(name, _) => f.debug_tuple("BuiltinFunc").field(name).finish(),
             Value::Nil => f.write_str("Nil"),
         }
@@ -208,15 +1080,58 @@ impl From<Literal> for Value {
     fn from(lit: Literal) -> Self {
         match lit {
             Literal::Boolean(b) => Value::Boolean(b),
-            Literal::Number(n) => Value::Number(n),
+            Literal::Int(n) => Value::Int(n),
+            Literal::BigInt(n) => Value::from(n),
+            Literal::Rational(n) => Value::from(n),
+            Literal::Float(n) => Value::Float(n),
+            Literal::String(s) => Value::String(s),
             Literal::Nil => Value::Nil,
         }
     }
 }
 
+impl From<i64> for Value {
+    fn from(value: i64) -> Value {
+        Value::Int(value)
+    }
+}
+
+// integers are only `BigInt`s if they don't fit in an `i64`
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Value {
+        match value.to_i64() {
+            Some(n) => Value::Int(n),
+            None => Value::BigInt(value),
+        }
+    }
+}
+
+// whole numbers become integers
+impl From<Rational> for Value {
+    fn from(value: Rational) -> Value {
+        if value.is_integer() {
+            Value::from(value.numer().clone())
+        } else {
+            Value::Rational(value)
+        }
+    }
+}
+
 impl From<f64> for Value {
     fn from(value: f64) -> Value {
-        Value::Number(value)
+        Value::Float(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::String(value)
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(value: &'a str) -> Value {
+        Value::String(value.to_string())
     }
 }
 
@@ -236,48 +1151,182 @@ impl<T: Into<Value>> From<Option<T>> for Value {
     }
 }
 
-// operation impls
+// operation impls, which fail if the operands are of the wrong types. operations on exact
+// numbers give an exact result, and otherwise the operands are converted to floats
 
 impl ops::Add for Value {
-    type Output = Value;
+    type Output = Result<Value>;
 
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs + rhs),
-            _ => Value::Nil,
+            // string concatenation
+            (Value::String(lhs), Value::String(rhs)) => Ok(Value::String(lhs + &rhs)),
+            (lhs, rhs) => arithmetic(
+                "+",
+                lhs,
+                rhs,
+                i64::checked_add,
+                |lhs, rhs| lhs + rhs,
+                |lhs, rhs| lhs + rhs,
+            ),
         }
     }
 }
 
 impl ops::Sub for Value {
-    type Output = Value;
+    type Output = Result<Value>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs - rhs),
-            _ => Value::Nil,
-        }
+        arithmetic(
+            "-",
+            self,
+            rhs,
+            i64::checked_sub,
+            |lhs, rhs| lhs - rhs,
+            |lhs, rhs| lhs - rhs,
+        )
     }
 }
 
 impl ops::Mul for Value {
-    type Output = Value;
+    type Output = Result<Value>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs * rhs),
-            _ => Value::Nil,
-        }
+        arithmetic(
+            "*",
+            self,
+            rhs,
+            i64::checked_mul,
+            |lhs, rhs| lhs * rhs,
+            |lhs, rhs| lhs * rhs,
+        )
     }
 }
 
+// division of exact numbers gives a rational, e.g. `1 3 /` is `1/3`, unless it is a whole number
 impl ops::Div for Value {
-    type Output = Value;
+    type Output = Result<Value>;
 
     fn div(self, rhs: Self) -> Self::Output {
+        division(
+            "/",
+            self,
+            rhs,
+            |lhs, rhs| match lhs.checked_rem(rhs) {
+                Some(0) => lhs.checked_div(rhs),
+                _ => None,
+            },
+            |lhs, rhs| lhs / rhs,
+            |lhs, rhs| lhs / rhs,
+        )
+    }
+}
+
+// the remainder has the same sign as the dividend
+impl ops::Rem for Value {
+    type Output = Result<Value>;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        division(
+            "%",
+            self,
+            rhs,
+            i64::checked_rem,
+            |lhs, rhs| lhs % rhs,
+            |lhs, rhs| lhs % rhs,
+        )
+    }
+}
+
+// apply an arithmetic operator to two numbers. `int` handles the common case of two `Int`s,
+// and if it overflows or either operand is a `BigInt` or `Rational` then `exact` is used
+fn arithmetic(
+    op: &'static str,
+    lhs: Value,
+    rhs: Value,
+    int: fn(i64, i64) -> Option<i64>,
+    exact: fn(&Rational, &Rational) -> Rational,
+    float: fn(f64, f64) -> f64,
+) -> Result<Value> {
+    if let (Value::Int(lhs), Value::Int(rhs)) = (&lhs, &rhs) {
+        if let Some(n) = int(*lhs, *rhs) {
+            return Ok(Value::Int(n));
+        }
+    }
+
+    match (lhs.as_exact(), rhs.as_exact()) {
+        (Some(lhs), Some(rhs)) => Ok(Value::from(exact(&lhs, &rhs))),
+        _ => float_op(op, lhs, rhs, float),
+    }
+}
+
+// apply a division operator, which is an error if both operands are exact and the divisor is
+// zero. floats follow IEEE 754, so dividing by zero gives an infinity or NaN
+fn division(
+    op: &'static str,
+    lhs: Value,
+    rhs: Value,
+    int: fn(i64, i64) -> Option<i64>,
+    exact: fn(&Rational, &Rational) -> Rational,
+    float: fn(f64, f64) -> f64,
+) -> Result<Value> {
+    // `BigInt`s and `Rational`s are never zero
+    if lhs.is_exact() && matches!(rhs, Value::Int(0)) {
+        return Err(InterpreterError::DivisionByZero.into());
+    }
+    arithmetic(op, lhs, rhs, int, exact, float)
+}
+
+// apply an operator to two numbers as floats
+fn float_op(op: &'static str, lhs: Value, rhs: Value, f: fn(f64, f64) -> f64) -> Result<Value> {
+    match (lhs.as_float(), rhs.as_float()) {
+        (Some(a), Some(b)) => Ok(Value::Float(f(a, b))),
+        _ => Err(mismatch(op, &lhs, &rhs)),
+    }
+}
+
+// boolean `and`
+impl ops::BitAnd for Value {
+    type Output = Result<Value>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Value::Boolean(lhs), Value::Boolean(rhs)) => Ok(Value::Boolean(lhs && rhs)),
+            (lhs, rhs) => Err(mismatch("and", &lhs, &rhs)),
+        }
+    }
+}
+
+// boolean `or`
+impl ops::BitOr for Value {
+    type Output = Result<Value>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs / rhs),
-            _ => Value::Nil,
+            (Value::Boolean(lhs), Value::Boolean(rhs)) => Ok(Value::Boolean(lhs || rhs)),
+            (lhs, rhs) => Err(mismatch("or", &lhs, &rhs)),
+        }
+    }
+}
+
+// boolean `not`
+impl ops::Not for Value {
+    type Output = Result<Value>;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Value::Boolean(b) => Ok(Value::Boolean(!b)),
+            value => Err(InterpreterError::ExpectedType("boolean", value.type_name()).into()),
         }
     }
 }
+
+// the error for a binary operator applied to operands of the wrong types
+fn mismatch(op: &'static str, lhs: &Value, rhs: &Value) -> Error {
+    InterpreterError::TypeMismatch {
+        op,
+        lhs_type: lhs.type_name(),
+        rhs_type: rhs.type_name(),
+    }
+    .into()
+}
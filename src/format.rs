@@ -0,0 +1,323 @@
+//! Format strings for the `format` builtin and interpolated strings such as `f"x = {x}"`
+//!
+//! A placeholder is written `{name:spec}`, where the name is empty for `format` and names a
+//! variable in an interpolated string. The spec is optional and follows Rust's syntax for
+//! alignment, width and precision, e.g. `{:>8}` or `{:.2}`. A literal brace is doubled.
+
+use std::fmt;
+use std::mem;
+
+use crate::eval::Value;
+use crate::num::{BigInt, Rational};
+
+/// The largest width or precision a placeholder may have
+///
+/// Rust's formatting takes widths and precisions up to `u16::MAX`, and a precision is passed
+/// to it as a width one larger.
+pub const MAX_COUNT: usize = u16::MAX as usize - 1;
+
+/// Errors in a format string or its arguments
+#[derive(Debug, Clone)]
+pub enum FormatError {
+    /// A `{` with no `}` after it
+    Unclosed,
+    /// A `}` which doesn't close a placeholder and isn't doubled
+    UnmatchedBrace,
+    /// A spec which isn't an alignment, width and precision, e.g. `{:x}`
+    InvalidSpec(String),
+    /// A placeholder passed to `format` which names a variable
+    NamedField(String),
+    /// A placeholder in an interpolated string which doesn't name a variable
+    ExpectedName(String),
+    /// A different number of values than placeholders
+    ArgumentCount(usize, usize),
+    /// A width or precision larger than `MAX_COUNT`
+    CountTooLarge(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Unclosed => f.write_str("unclosed '{' in format string"),
+            FormatError::UnmatchedBrace => f.write_str("unmatched '}' in format string"),
+            FormatError::InvalidSpec(spec) => write!(f, "invalid format spec '{}'", spec),
+            FormatError::NamedField(name) => write!(f, "unexpected name '{}' in placeholder", name),
+            FormatError::ExpectedName(name) => {
+                write!(
+                    f,
+                    "expected a variable name in placeholder but got '{}'",
+                    name
+                )
+            }
+            FormatError::ArgumentCount(expected, got) => write!(
+                f,
+                "format string has {} placeholders but got {} values",
+                expected, got
+            ),
+            FormatError::CountTooLarge(count) => write!(
+                f,
+                "width or precision {} is larger than the limit of {}",
+                count, MAX_COUNT
+            ),
+        }
+    }
+}
+
+/// Where a value is placed when it is narrower than the width of its placeholder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// How a value is written in a placeholder: `[[fill]align][width][.precision]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    /// The alignment, which by default is right for numbers and left for everything else
+    pub align: Option<Align>,
+    pub width: usize,
+    /// The number of digits after the decimal point of a number, or the maximum number of
+    /// characters of anything else
+    pub precision: Option<usize>,
+}
+
+impl Default for FormatSpec {
+    fn default() -> FormatSpec {
+        FormatSpec {
+            fill: ' ',
+            align: None,
+            width: 0,
+            precision: None,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Parse the part of a placeholder after the `:`
+    pub fn parse(spec: &str) -> Result<FormatSpec, FormatError> {
+        let invalid = || FormatError::InvalidSpec(spec.to_string());
+        let mut result = FormatSpec::default();
+
+        // the fill character is only given along with an alignment
+        let mut chars = spec.chars();
+        let rest = match (chars.next(), chars.next().and_then(align)) {
+            (Some(fill), Some(align)) => {
+                result.fill = fill;
+                result.align = Some(align);
+                chars.as_str()
+            }
+            _ => match spec.chars().next().and_then(align) {
+                Some(align) => {
+                    result.align = Some(align);
+                    &spec[1..]
+                }
+                None => spec,
+            },
+        };
+
+        let (width, precision) = match rest.find('.') {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+        if !width.is_empty() {
+            result.width = parse_count(width).ok_or_else(invalid)??;
+        }
+        if let Some(precision) = precision {
+            result.precision = Some(parse_count(precision).ok_or_else(invalid)??);
+        }
+        Ok(result)
+    }
+
+    /// Write `value` as it is printed, padded and rounded as the spec describes
+    pub fn apply(&self, value: &Value) -> String {
+        let text = match (self.precision, value) {
+            (Some(precision), Value::Float(n)) => format!("{:.*}", precision, n),
+            // ints and fractions are rounded exactly rather than by converting them to floats
+            (Some(precision), value) if value.is_number() => {
+                fixed(&value.as_exact().unwrap(), precision)
+            }
+            (Some(precision), value) => value.to_string().chars().take(precision).collect(),
+            (None, value) => value.to_string(),
+        };
+
+        let len = text.chars().count();
+        if len >= self.width {
+            return text;
+        }
+        let padding = self.width - len;
+        let default = if value.is_number() {
+            Align::Right
+        } else {
+            Align::Left
+        };
+        let (before, after) = match self.align.unwrap_or(default) {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        };
+
+        let fill = |n| std::iter::repeat_n(self.fill, n);
+        fill(before)
+            .chain(text.chars())
+            .chain(fill(after))
+            .collect()
+    }
+}
+
+// written back as it was parsed
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(align) = self.align {
+            if self.fill != ' ' {
+                write!(f, "{}", self.fill)?;
+            }
+            f.write_str(match align {
+                Align::Left => "<",
+                Align::Right => ">",
+                Align::Center => "^",
+            })?;
+        }
+        if self.width > 0 {
+            write!(f, "{}", self.width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        Ok(())
+    }
+}
+
+/// A part of a format string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    Text(String),
+    /// A placeholder, with the name written before its spec
+    Field(String, FormatSpec),
+}
+
+// written as in a format string, so braces in text are doubled
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Piece::Text(text) => f.write_str(&text.replace('{', "{{").replace('}', "}}")),
+            Piece::Field(name, spec) if *spec == FormatSpec::default() => write!(f, "{{{}}}", name),
+            Piece::Field(name, spec) => write!(f, "{{{}:{}}}", name, spec),
+        }
+    }
+}
+
+/// Split a format string into text and placeholders
+pub fn parse(template: &str) -> Result<Vec<Piece>, FormatError> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        let rest = chars.as_str();
+        match c {
+            '{' if rest.starts_with('{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if rest.starts_with('}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return Err(FormatError::UnmatchedBrace),
+            '{' => {
+                let end = rest.find('}').ok_or(FormatError::Unclosed)?;
+                let (name, spec) = match rest[..end].find(':') {
+                    Some(i) => (&rest[..i], &rest[i + 1..end]),
+                    None => (&rest[..end], ""),
+                };
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(mem::take(&mut text)));
+                }
+                pieces.push(Piece::Field(
+                    name.trim().to_string(),
+                    FormatSpec::parse(spec)?,
+                ));
+                chars = rest[end + 1..].chars();
+            }
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    Ok(pieces)
+}
+
+/// The number of placeholders in `pieces`, which is an error if any of them have names
+pub fn count_fields(pieces: &[Piece]) -> Result<usize, FormatError> {
+    let mut fields = 0;
+    for piece in pieces {
+        match piece {
+            Piece::Field(name, _) if !name.is_empty() => {
+                return Err(FormatError::NamedField(name.clone()))
+            }
+            Piece::Field(..) => fields += 1,
+            Piece::Text(_) => {}
+        }
+    }
+    Ok(fields)
+}
+
+/// Fill the placeholders of `pieces` with `args` in order. Placeholders must not have names.
+pub fn format(pieces: &[Piece], args: &[Value]) -> Result<String, FormatError> {
+    let fields = count_fields(pieces)?;
+    if fields != args.len() {
+        return Err(FormatError::ArgumentCount(fields, args.len()));
+    }
+
+    let mut args = args.iter();
+    let mut result = String::new();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => result.push_str(text),
+            // there are as many arguments as placeholders
+            Piece::Field(_, spec) => result.push_str(&spec.apply(args.next().unwrap())),
+        }
+    }
+    Ok(result)
+}
+
+fn align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        _ => None,
+    }
+}
+
+// a width or precision, which must be all digits and no more than `MAX_COUNT`
+fn parse_count(digits: &str) -> Option<Result<usize, FormatError>> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(match digits.parse() {
+        Ok(n) if n <= MAX_COUNT => Ok(n),
+        _ => Err(FormatError::CountTooLarge(digits.to_string())),
+    })
+}
+
+// write an exact number with `precision` digits after the decimal point, rounding halfway cases
+// away from zero
+fn fixed(n: &Rational, precision: usize) -> String {
+    // the precision is at most `MAX_COUNT`
+    let scale = Rational::from(BigInt::from(10).pow(precision as u32));
+    let scaled = (n * &scale).round();
+
+    // pad with zeros so there is at least one digit before the decimal point
+    let digits = format!("{:0>1$}", scaled.abs().to_string(), precision + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - precision);
+    let sign = if scaled.is_negative() { "-" } else { "" };
+    match precision {
+        0 => format!("{}{}", sign, whole),
+        _ => format!("{}{}.{}", sign, whole, fraction),
+    }
+}
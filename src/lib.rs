@@ -1,7 +1,5 @@
 //! # Frothy
 //! A postfix expression language loosely inspired by Forth
-//!
-//! TODO use generic binary op enum variant instead of `Add`, `Multiply` etc.
 
 use eval::Interpreter;
 
@@ -9,10 +7,16 @@ use crate::error::Result;
 use crate::eval::Value;
 
 pub mod ast;
+pub mod diagnostic;
 pub mod error;
 pub mod eval;
+pub mod format;
+pub mod io;
+pub mod map;
+pub mod num;
+pub mod span;
+mod stdlib;
 pub mod token;
-pub mod util;
 
 pub fn eval(program: &str) -> Result<Vec<Value>> {
     Interpreter::new().interpret(program)
@@ -0,0 +1,130 @@
+//! The insertion-ordered map held by [`Value::Map`](../eval/enum.Value.html)
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::error::Result;
+use crate::eval::{InterpreterError, Value};
+
+/// A map from numbers, booleans or strings to values, which iterates in insertion order
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(Value, Value)>,
+    // the position of each key in `entries`
+    indices: HashMap<Key, usize>,
+}
+
+impl Map {
+    pub fn new() -> Map {
+        Map::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the value for `key`, or an error if `key` can't be used as a key
+    pub fn get(&self, key: &Value) -> Result<Option<&Value>> {
+        let i = self.indices.get(&Key::new(key)?);
+        Ok(i.map(|&i| &self.entries[i].1))
+    }
+
+    pub fn contains_key(&self, key: &Value) -> Result<bool> {
+        Ok(self.indices.contains_key(&Key::new(key)?))
+    }
+
+    /// Set the value for `key`, returning its old value. A new key is added to the end.
+    pub fn insert(&mut self, key: Value, value: Value) -> Result<Option<Value>> {
+        let hashed = Key::new(&key)?;
+        match self.indices.get(&hashed) {
+            Some(&i) => Ok(Some(std::mem::replace(&mut self.entries[i].1, value))),
+            None => {
+                self.indices.insert(hashed, self.entries.len());
+                self.entries.push((key, value));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove `key`, returning its value if it was in the map
+    pub fn remove(&mut self, key: &Value) -> Result<Option<Value>> {
+        let i = match self.indices.remove(&Key::new(key)?) {
+            Some(i) => i,
+            None => return Ok(None),
+        };
+
+        // keep the order of the remaining entries, so the ones after `i` move back one place
+        let (_, value) = self.entries.remove(i);
+        for index in self.indices.values_mut() {
+            if *index > i {
+                *index -= 1;
+            }
+        }
+        Ok(Some(value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Value, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// maps are equal if they have the same entries, in any order
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(k, v)| matches!(other.get(k), Ok(Some(other)) if v == other))
+    }
+}
+
+// a value which can be used as a key. unlike `Value`'s equality, NaN is equal to itself so
+// that every key can be found again
+#[derive(Debug, Clone)]
+struct Key(Value);
+
+impl Key {
+    fn new(value: &Value) -> Result<Key> {
+        match value {
+            value if value.is_number() => Ok(Key(value.clone())),
+            Value::Boolean(_) | Value::String(_) => Ok(Key(value.clone())),
+            _ => Err(InterpreterError::UnhashableKey(value.type_name()).into()),
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Key) -> bool {
+        match (&self.0, &other.0) {
+            (Value::Float(a), Value::Float(b)) => a.is_nan() && b.is_nan() || a == b,
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
@@ -0,0 +1,882 @@
+//! Arbitrary-precision integers and exact rationals, which frothy uses for numbers that don't
+//! fit in an `i64` and for division that doesn't round
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops;
+
+/// An arbitrary-precision integer
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    // the magnitude in base 2^32, least significant digit first and without trailing zeros,
+    // so zero has no digits
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    // create an integer from a sign and a magnitude which may have trailing zeros
+    fn new(negative: bool, digits: Vec<u32>) -> BigInt {
+        let digits = trim(digits);
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::new(false, self.digits.clone())
+    }
+
+    /// Parse digits in the given radix, with an optional leading `-`
+    pub fn parse(s: &str, radix: u32) -> Option<BigInt> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut magnitude = vec![];
+        for c in digits.chars() {
+            let digit = c.to_digit(radix)?;
+            mul_add_small(&mut magnitude, radix, digit);
+        }
+        Some(BigInt::new(negative, magnitude))
+    }
+
+    /// Convert to an `i64` if the integer is small enough
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = match self.digits[..] {
+            [] => 0,
+            [low] => u64::from(low),
+            [low, high] => u64::from(low) | u64::from(high) << 32,
+            _ => return None,
+        };
+
+        if self.negative {
+            // the most negative `i64` has no positive counterpart
+            match magnitude.cmp(&(1 << 63)) {
+                Ordering::Less => Some(-(magnitude as i64)),
+                Ordering::Equal => Some(i64::MIN),
+                Ordering::Greater => None,
+            }
+        } else if magnitude <= i64::MAX as u64 {
+            Some(magnitude as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Convert to the nearest float, which is infinite if the integer is too large
+    pub fn to_f64(&self) -> f64 {
+        // keep the top 64 bits, setting the lowest of them if any bit below them is set so
+        // that rounding them to a float still rounds the whole integer correctly
+        let shift = self.bits().saturating_sub(64);
+        let (top, sticky) = self.bits_from(shift);
+        let magnitude = scale((top | u64::from(sticky)) as f64, shift as i64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// The number of bits in the magnitude, which is 0 for zero
+    pub fn bits(&self) -> usize {
+        match self.digits.last() {
+            Some(last) => self.digits.len() * 32 - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    // the 64 bits of the magnitude starting at bit `start`, and whether any bit below them is set
+    fn bits_from(&self, start: usize) -> (u64, bool) {
+        let (word, offset) = (start / 32, start % 32);
+        let digit = |i: usize| u128::from(self.digits.get(i).copied().unwrap_or(0));
+        let window = digit(word) | digit(word + 1) << 32 | digit(word + 2) << 64;
+        let below = u64::from(digit(word) as u32) & ((1 << offset) - 1);
+        let sticky = below != 0 || self.digits.iter().take(word).any(|&d| d != 0);
+        ((window >> offset) as u64, sticky)
+    }
+
+    /// Divide by `other`, rounding towards zero, and return the quotient and remainder. The
+    /// remainder has the same sign as `self`.
+    ///
+    /// Panics if `other` is zero.
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        assert!(!other.is_zero(), "division by zero");
+        let (quotient, remainder) = div_rem_magnitude(&self.digits, &other.digits);
+        (
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        )
+    }
+
+    /// The greatest common divisor of two integers, which is never negative
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while !b.is_zero() {
+            let (_, remainder) = a.div_rem(&b);
+            a = b;
+            b = remainder;
+        }
+        a
+    }
+
+    /// Raise to the power `exp`, by repeated squaring
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    // multiply by 2^bits
+    fn shl(&self, bits: usize) -> BigInt {
+        let mut digits = vec![0; bits / 32];
+        let shift = bits % 32;
+        let mut carry = 0;
+        for &digit in &self.digits {
+            let shifted = u64::from(digit) << shift | carry;
+            digits.push(shifted as u32);
+            carry = shifted >> 32;
+        }
+        digits.push(carry as u32);
+        BigInt::new(self.negative, digits)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> BigInt {
+        let magnitude = n.unsigned_abs();
+        BigInt::new(n < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.digits, &other.digits),
+            (true, true) => cmp_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl ops::Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitude(&self.digits, &other.digits));
+        }
+
+        // the signs differ, so subtract the smaller magnitude from the larger
+        match cmp_magnitude(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::new(other.negative, sub_magnitude(&other.digits, &self.digits))
+            }
+            _ => BigInt::new(self.negative, sub_magnitude(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl ops::Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl ops::Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_magnitude(&self.digits, &other.digits),
+        )
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // split off 9 decimal digits at a time, least significant first
+        let mut chunks = vec![];
+        let mut magnitude = self.digits.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = div_rem_small(&magnitude, 1_000_000_000);
+            chunks.push(remainder);
+            magnitude = quotient;
+        }
+
+        let mut s = String::new();
+        match chunks.split_last() {
+            Some((first, rest)) => {
+                s.push_str(&first.to_string());
+                for chunk in rest.iter().rev() {
+                    s.push_str(&format!("{:09}", chunk));
+                }
+            }
+            None => s.push('0'),
+        }
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// An exact fraction of two [`BigInt`](struct.BigInt.html)s, always kept in its lowest terms
+/// with a positive denominator
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Rational {
+    /// Create the fraction `numer / denom`
+    ///
+    /// Panics if `denom` is zero.
+    pub fn new(numer: BigInt, denom: BigInt) -> Rational {
+        assert!(!denom.is_zero(), "zero denominator");
+
+        // integers are already in their lowest terms
+        if denom == BigInt::one() {
+            return Rational { numer, denom };
+        }
+
+        let gcd = numer.gcd(&denom);
+        let (mut numer, _) = numer.div_rem(&gcd);
+        let (mut denom, _) = denom.div_rem(&gcd);
+        if denom.is_negative() {
+            numer = -&numer;
+            denom = -&denom;
+        }
+        Rational { numer, denom }
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::one()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    /// Convert a finite float to the fraction it represents exactly
+    pub fn from_f64(n: f64) -> Option<Rational> {
+        if !n.is_finite() {
+            return None;
+        }
+
+        // a float is a 53 bit integer multiplied by a power of two
+        let bits = n.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = (bits & ((1 << 52) - 1)) as i64;
+        let (mantissa, exponent) = match exponent {
+            // subnormal numbers have no implicit leading 1
+            0 => (fraction, -1074),
+            _ => (fraction | 1 << 52, exponent - 1075),
+        };
+
+        let mantissa = BigInt::from(if n < 0.0 { -mantissa } else { mantissa });
+        Some(if exponent >= 0 {
+            Rational::from(mantissa.shl(exponent as usize))
+        } else {
+            Rational::new(mantissa, BigInt::one().shl(-exponent as usize))
+        })
+    }
+
+    /// Convert to the nearest float
+    pub fn to_f64(&self) -> f64 {
+        // converting the numerator and denominator separately overflows when both are huge, so
+        // divide them exactly with the numerator shifted until the quotient has at least 65
+        // bits, then shift the result back. the remainder becomes a sticky lowest bit so that
+        // the quotient rounds correctly.
+        let shift = 66 + self.denom.bits() as i64 - self.numer.bits() as i64;
+        let (numer, denom) = if shift >= 0 {
+            (self.numer.abs().shl(shift as usize), self.denom.clone())
+        } else {
+            (self.numer.abs(), self.denom.shl(-shift as usize))
+        };
+        let (quotient, remainder) = numer.div_rem(&denom);
+        let sticky = BigInt::from(i64::from(!remainder.is_zero()));
+        let quotient = &quotient.shl(1) + &sticky;
+
+        let magnitude = scale(quotient.to_f64(), -(shift + 1));
+        if self.numer.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Round towards zero
+    pub fn trunc(&self) -> BigInt {
+        self.numer.div_rem(&self.denom).0
+    }
+
+    /// Round towards negative infinity
+    pub fn floor(&self) -> BigInt {
+        let (quotient, remainder) = self.numer.div_rem(&self.denom);
+        if remainder.is_negative() {
+            &quotient - &BigInt::one()
+        } else {
+            quotient
+        }
+    }
+
+    /// Round towards positive infinity
+    pub fn ceil(&self) -> BigInt {
+        let (quotient, remainder) = self.numer.div_rem(&self.denom);
+        if remainder.is_zero() || remainder.is_negative() {
+            quotient
+        } else {
+            &quotient + &BigInt::one()
+        }
+    }
+
+    /// Round to the nearest integer, rounding halfway cases away from zero
+    pub fn round(&self) -> BigInt {
+        let half = Rational::new(BigInt::one(), BigInt::from(2));
+        if self.numer.is_negative() {
+            (self - &half).ceil()
+        } else {
+            (self + &half).floor()
+        }
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+
+    /// Raise to the power `exp`
+    pub fn pow(&self, exp: u32) -> Rational {
+        // the powers of coprime integers are still coprime, so this is in lowest terms
+        Rational {
+            numer: self.numer.pow(exp),
+            denom: self.denom.pow(exp),
+        }
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(n: BigInt) -> Rational {
+        Rational {
+            numer: n,
+            denom: BigInt::one(),
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Rational {
+        Rational::from(BigInt::from(n))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        Rational::new(
+            &(&self.numer * &other.denom) + &(&other.numer * &self.denom),
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl ops::Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        Rational::new(
+            &(&self.numer * &other.denom) - &(&other.numer * &self.denom),
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl ops::Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Rational {
+        Rational::new(&self.numer * &other.numer, &self.denom * &other.denom)
+    }
+}
+
+// panics if `other` is zero
+impl ops::Div for &Rational {
+    type Output = Rational;
+
+    fn div(self, other: &Rational) -> Rational {
+        Rational::new(&self.numer * &other.denom, &self.denom * &other.numer)
+    }
+}
+
+// the remainder of division rounding towards zero, so it has the same sign as `self`. panics if
+// `other` is zero
+impl ops::Rem for &Rational {
+    type Output = Rational;
+
+    fn rem(self, other: &Rational) -> Rational {
+        let quotient = Rational::from((self / other).trunc());
+        self - &(other * &quotient)
+    }
+}
+
+// written as `numer/denom`, which frothy reads back as the same fraction
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            fmt::Display::fmt(&self.numer, f)
+        } else {
+            f.pad(&format!("{}/{}", self.numer, self.denom))
+        }
+    }
+}
+
+impl fmt::Debug for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// operations on magnitudes, which are little-endian base 2^32 digits without trailing zeros
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+    for (i, &digit) in long.iter().enumerate() {
+        let total = u64::from(digit) + u64::from(*short.get(i).unwrap_or(&0)) + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    sum.push(carry as u32);
+    sum
+}
+
+// subtract `b` from `a`, which must be at least as large
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &digit) in a.iter().enumerate() {
+        let total = i64::from(digit) - i64::from(*b.get(i).unwrap_or(&0)) - borrow;
+        difference.push(total.rem_euclid(1 << 32) as u32);
+        borrow = if total < 0 { 1 } else { 0 };
+    }
+    trim(difference)
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let total = u64::from(product[i + j]) + u64::from(x) * u64::from(y) + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    trim(product)
+}
+
+// multiply `a` by `factor` and add `addend`, in place
+fn mul_add_small(a: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = u64::from(addend);
+    for digit in a.iter_mut() {
+        let total = u64::from(*digit) * u64::from(factor) + carry;
+        *digit = total as u32;
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        a.push(carry as u32);
+    }
+}
+
+fn div_rem_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0u64;
+    for i in (0..a.len()).rev() {
+        let current = remainder << 32 | u64::from(a[i]);
+        quotient[i] = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+    (trim(quotient), remainder as u32)
+}
+
+// long division a digit at a time, by Knuth's algorithm D (The Art of Computer Programming,
+// volume 2, section 4.3.1)
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    if let [divisor] = b {
+        let (quotient, remainder) = div_rem_small(a, *divisor);
+        return (quotient, trim(vec![remainder]));
+    }
+
+    // shift both so the top bit of the divisor is set, which makes each estimated quotient
+    // digit at most two too large. the dividend gains a digit to hold what is shifted out
+    let shift = b[b.len() - 1].leading_zeros();
+    let mut b = shl_digits(b, shift);
+    b.pop();
+    let mut a = shl_digits(a, shift);
+
+    let n = b.len();
+    let (top, next) = (u64::from(b[n - 1]), u64::from(b[n - 2]));
+    let mut quotient = vec![0; a.len() - n];
+    for j in (0..quotient.len()).rev() {
+        // estimate the quotient digit from the top digits of the remainder, then correct it
+        // using the next digit of the divisor, after which it is at most one too large
+        let current = u64::from(a[j + n]) << 32 | u64::from(a[j + n - 1]);
+        let mut estimate = current / top;
+        let mut rest = current % top;
+        while estimate > u64::from(u32::MAX)
+            || estimate * next > (rest << 32 | u64::from(a[j + n - 2]))
+        {
+            estimate -= 1;
+            rest += top;
+            if rest > u64::from(u32::MAX) {
+                break;
+            }
+        }
+
+        // subtract `estimate * b` from the remainder
+        let mut borrow = 0;
+        let mut carry = 0;
+        for i in 0..n {
+            let product = estimate * u64::from(b[i]) + carry;
+            carry = product >> 32;
+            let diff = i64::from(a[i + j]) - borrow - i64::from(product as u32);
+            a[i + j] = diff as u32;
+            borrow = i64::from(diff < 0);
+        }
+        let diff = i64::from(a[j + n]) - borrow - carry as i64;
+        a[j + n] = diff as u32;
+
+        // the estimate was one too large, so add back one `b`
+        if diff < 0 {
+            estimate -= 1;
+            let mut carry = 0;
+            for i in 0..n {
+                let sum = u64::from(a[i + j]) + u64::from(b[i]) + carry;
+                a[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            a[j + n] = a[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = estimate as u32;
+    }
+
+    // the remainder is left in the low digits, still shifted
+    let remainder = (0..n)
+        .map(|i| {
+            let pair = u64::from(a[i + 1]) << 32 | u64::from(a[i]);
+            (pair >> shift) as u32
+        })
+        .collect();
+    (trim(quotient), trim(remainder))
+}
+
+// shift digits left by less than 32 bits, always adding a digit for the bits shifted out
+fn shl_digits(digits: &[u32], shift: u32) -> Vec<u32> {
+    let mut shifted = Vec::with_capacity(digits.len() + 1);
+    let mut carry = 0;
+    for &digit in digits {
+        let wide = u64::from(digit) << shift | carry;
+        shifted.push(wide as u32);
+        carry = wide >> 32;
+    }
+    shifted.push(carry as u32);
+    shifted
+}
+
+// multiply by 2^exp, in steps so that the power of two itself doesn't overflow to infinity or
+// underflow to zero
+fn scale(mut n: f64, mut exp: i64) -> f64 {
+    while exp > 1000 && n.is_finite() {
+        n *= 2f64.powi(1000);
+        exp -= 1000;
+    }
+    while exp < -1000 && n != 0.0 {
+        n *= 2f64.powi(-1000);
+        exp += 1000;
+    }
+    // `n` is already infinite or zero if `exp` is still out of range
+    if exp.abs() > 1000 {
+        return n;
+    }
+    n * 2f64.powi(exp as i32)
+}
+
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::{Token, Tokens};
+
+    fn big(s: &str) -> BigInt {
+        BigInt::parse(s, 10).unwrap()
+    }
+
+    fn ratio(numer: i64, denom: i64) -> Rational {
+        Rational::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    #[test]
+    fn div_rem_signs() {
+        for &(a, b) in &[(7, 2), (-7, 2), (7, -2), (-7, -2), (6, 3), (1, 5)] {
+            let (quotient, remainder) = BigInt::from(a).div_rem(&BigInt::from(b));
+            assert_eq!(quotient, BigInt::from(a / b), "{} / {}", a, b);
+            assert_eq!(remainder, BigInt::from(a % b), "{} % {}", a, b);
+        }
+    }
+
+    #[test]
+    fn div_rem_multiple_digits() {
+        let a = big("123456789012345678901234567890123456789");
+        let b = big("98765432109876543210");
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(quotient, big("1249999988609375000"));
+        assert_eq!(remainder, big("15297067891529706789"));
+        assert_eq!(&(&quotient * &b) + &remainder, a);
+
+        // a divisor with a full top digit, so every bit of the remainder is used
+        let b = big("18446744073709551615");
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(&(&quotient * &b) + &remainder, a);
+        assert!(remainder < b);
+    }
+
+    #[test]
+    fn div_rem_random() {
+        // digits near the extremes make the quotient estimates most often wrong
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut digit = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            match seed % 4 {
+                0 => 0,
+                1 => u32::MAX,
+                2 => 1 << 31,
+                _ => (seed >> 32) as u32,
+            }
+        };
+        for len in 2..12 {
+            for _ in 0..200 {
+                let a = BigInt::new(false, (0..len * 2).map(|_| digit()).collect());
+                let b = BigInt::new(false, (0..len).map(|_| digit()).collect());
+                if b.is_zero() {
+                    continue;
+                }
+                let (quotient, remainder) = a.div_rem(&b);
+                assert_eq!(&(&quotient * &b) + &remainder, a, "{} / {}", a, b);
+                assert!(remainder < b, "{} / {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn div_rem_adds_back() {
+        // a quotient digit which is still one too large after the estimate is corrected
+        let max = u32::MAX;
+        let a = BigInt::new(false, vec![0, 1 << 31, max, max, max, 1 << 31]);
+        let b = BigInt::new(false, vec![max, 0, 1 << 31]);
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(quotient.digits, vec![0xffff_fffe, 0xffff_fffd, 1, 1]);
+        assert_eq!(remainder.digits, vec![0xffff_fffe, 0x7fff_ffff, 3]);
+    }
+
+    #[test]
+    fn div_rem_large_operands() {
+        // dividing thousands of bits takes a few milliseconds, where it once took seconds
+        let a = &BigInt::from(3).pow(7600) + &BigInt::from(12345);
+        let b = &BigInt::from(7).pow(4050) - &BigInt::one();
+        let start = std::time::Instant::now();
+        for _ in 0..20 {
+            let (quotient, remainder) = a.div_rem(&b);
+            assert_eq!(&(&quotient * &b) + &remainder, a);
+        }
+        assert!(start.elapsed().as_secs() < 5, "took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn gcd() {
+        assert_eq!(BigInt::from(12).gcd(&BigInt::from(18)), BigInt::from(6));
+        assert_eq!(BigInt::from(-12).gcd(&BigInt::from(18)), BigInt::from(6));
+        assert_eq!(BigInt::from(0).gcd(&BigInt::from(-5)), BigInt::from(5));
+        assert_eq!(BigInt::from(17).gcd(&BigInt::from(5)), BigInt::one());
+        let a = &big("18446744073709551616") * &BigInt::from(3);
+        let b = &big("18446744073709551616") * &BigInt::from(5);
+        assert_eq!(a.gcd(&b), big("18446744073709551616"));
+    }
+
+    #[test]
+    fn rationals_are_in_lowest_terms() {
+        let n = ratio(6, -4);
+        assert_eq!(n.numer(), &BigInt::from(-3));
+        assert_eq!(n.denom(), &BigInt::from(2));
+        assert!(ratio(4, 2).is_integer());
+    }
+
+    #[test]
+    fn from_f64() {
+        assert_eq!(Rational::from_f64(0.5), Some(ratio(1, 2)));
+        assert_eq!(Rational::from_f64(-2.75), Some(ratio(-11, 4)));
+        assert_eq!(Rational::from_f64(3.0), Some(Rational::from(3)));
+        assert_eq!(
+            Rational::from_f64(2f64.powi(70)),
+            Some(Rational::from(big("1180591620717411303424")))
+        );
+        let tiny = Rational::from_f64(f64::MIN_POSITIVE / 4.0).unwrap();
+        assert_eq!(tiny.numer(), &BigInt::one());
+        assert_eq!(tiny.denom(), &BigInt::one().shl(1024));
+        assert_eq!(Rational::from_f64(f64::INFINITY), None);
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+    }
+
+    #[test]
+    fn to_f64() {
+        for &n in &[0.0, 0.1, -2.75, 1e300, -1e-300, 5e-324, f64::MAX] {
+            assert_eq!(Rational::from_f64(n).unwrap().to_f64(), n);
+        }
+        assert_eq!(ratio(1, 3).to_f64(), 1.0 / 3.0);
+        assert_eq!(big("9007199254740993").to_f64(), 9007199254740992.0);
+
+        // both parts are too large for a float but their ratio isn't
+        let huge = BigInt::from(10).pow(400);
+        let n = Rational::new(&huge + &BigInt::one(), huge.clone());
+        assert_eq!(n.to_f64(), 1.0);
+        assert_eq!(huge.to_f64(), f64::INFINITY);
+        assert_eq!(Rational::new(BigInt::one(), huge).to_f64(), 0.0);
+    }
+
+    #[test]
+    fn rounding() {
+        // value, trunc, floor, ceil, round
+        for &(numer, denom, trunc, floor, ceil, round) in &[
+            (7, 2, 3, 3, 4, 4),
+            (-7, 2, -3, -4, -3, -4),
+            (5, 3, 1, 1, 2, 2),
+            (-5, 3, -1, -2, -1, -2),
+            (-4, 3, -1, -2, -1, -1),
+            (-1, 2, 0, -1, 0, -1),
+            (-6, 1, -6, -6, -6, -6),
+        ] {
+            let n = ratio(numer, denom);
+            assert_eq!(n.trunc(), BigInt::from(trunc), "{} trunc", n);
+            assert_eq!(n.floor(), BigInt::from(floor), "{} floor", n);
+            assert_eq!(n.ceil(), BigInt::from(ceil), "{} ceil", n);
+            assert_eq!(n.round(), BigInt::from(round), "{} round", n);
+        }
+    }
+
+    #[test]
+    fn display_reads_back() {
+        let numbers = vec![
+            Rational::from(big("-123456789012345678901234567890")),
+            Rational::from(big("1000000000000000000000")),
+            Rational::new(big("-100000000000000000000000001"), big("3")),
+            ratio(22, 7),
+        ];
+        for n in numbers {
+            let s = n.to_string();
+            let read = match Tokens::new(&s).next() {
+                Some(Ok(token)) => token.node,
+                token => panic!("{}: expected a number but got {:?}", s, token),
+            };
+            match read {
+                Token::BigInt(read) => assert_eq!(Rational::from(read), n),
+                Token::Rational(read) => assert_eq!(read, n),
+                token => panic!("{}: expected a number but got {:?}", s, token),
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Builtins which call a function for each item of a list
+//!
+//! The function is called with the item on a stack of its own, so it can't reach any other
+//! values, and its result is the last value it leaves.
+
+use std::cmp::Ordering;
+
+use super::{expected, new_list, pop_list};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("map", map);
+    ctx.builtin_func("filter", filter);
+    ctx.builtin_func("fold", fold);
+    ctx.builtin_func("each", each);
+    ctx.builtin_func("any", any);
+    ctx.builtin_func("all", all);
+    ctx.builtin_func("sort", sort);
+    ctx.builtin_func("sort_by", sort_by);
+    ctx.builtin_func("zip", zip);
+}
+
+// list f -- list
+fn map(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    let mut mapped = Vec::with_capacity(items.len());
+    for item in items {
+        mapped.push(apply(interp, &f, vec![item])?);
+    }
+    interp.push(new_list(mapped));
+    Ok(())
+}
+
+// list f -- list
+fn filter(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    let mut kept = vec![];
+    for item in items {
+        if test(interp, &f, item.clone())? {
+            kept.push(item);
+        }
+    }
+    interp.push(new_list(kept));
+    Ok(())
+}
+
+// list init f -- value
+fn fold(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let mut acc = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    for item in items {
+        acc = apply(interp, &f, vec![acc, item])?;
+    }
+    interp.push(acc);
+    Ok(())
+}
+
+// list f --
+fn each(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    // any results are discarded
+    for item in items {
+        interp.call_with(&f, vec![item])?;
+    }
+    Ok(())
+}
+
+// list f -- bool
+fn any(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    // stop at the first item which passes
+    let mut found = false;
+    for item in items {
+        if test(interp, &f, item)? {
+            found = true;
+            break;
+        }
+    }
+    interp.push(Value::Boolean(found));
+    Ok(())
+}
+
+// list f -- bool
+fn all(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    // stop at the first item which fails
+    let mut passed = true;
+    for item in items {
+        if !test(interp, &f, item)? {
+            passed = false;
+            break;
+        }
+    }
+    interp.push(Value::Boolean(passed));
+    Ok(())
+}
+
+// list -- list
+fn sort(interp: &mut Interpreter) -> Result<()> {
+    let list = pop_list(interp)?;
+    let keyed = list
+        .borrow()
+        .iter()
+        .map(|item| (item.clone(), item.clone()))
+        .collect();
+    interp.push(new_list(sorted(keyed)?));
+    Ok(())
+}
+
+// list f -- list
+fn sort_by(interp: &mut Interpreter) -> Result<()> {
+    let f = interp.pop()?;
+    let list = pop_list(interp)?;
+    let items = list.borrow().clone();
+
+    // find each item's key once, rather than every time it is compared
+    let mut keyed = Vec::with_capacity(items.len());
+    for item in items {
+        keyed.push((apply(interp, &f, vec![item.clone()])?, item));
+    }
+    interp.push(new_list(sorted(keyed)?));
+    Ok(())
+}
+
+// a b -- list
+fn zip(interp: &mut Interpreter) -> Result<()> {
+    let b = pop_list(interp)?;
+    let a = pop_list(interp)?;
+
+    // pairs stop at the end of the shorter list
+    let pairs = a
+        .borrow()
+        .iter()
+        .zip(b.borrow().iter())
+        .map(|(a, b)| new_list(vec![a.clone(), b.clone()]))
+        .collect();
+    interp.push(new_list(pairs));
+    Ok(())
+}
+
+// call `f` with `args` and take its result
+fn apply(interp: &mut Interpreter, f: &Value, args: Vec<Value>) -> Result<Value> {
+    interp
+        .call_with(f, args)?
+        .pop()
+        .ok_or_else(|| InterpreterError::StackUnderflow.into())
+}
+
+// call `f` with `item` and take its result, which must be a boolean
+fn test(interp: &mut Interpreter, f: &Value, item: Value) -> Result<bool> {
+    match apply(interp, f, vec![item])? {
+        Value::Boolean(b) => Ok(b),
+        value => Err(expected("boolean", &value)),
+    }
+}
+
+// stably sort `(key, item)` pairs by their keys and take the items
+fn sorted(mut keyed: Vec<(Value, Value)>) -> Result<Vec<Value>> {
+    let mut error = None;
+    keyed.sort_by(|(a, _), (b, _)| {
+        a.compare(b).unwrap_or_else(|| {
+            error.get_or_insert(InterpreterError::Incomparable(a.type_name(), b.type_name()));
+            Ordering::Equal
+        })
+    });
+
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok(keyed.into_iter().map(|(_, item)| item).collect()),
+    }
+}
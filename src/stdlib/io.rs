@@ -0,0 +1,95 @@
+//! Builtins which write to the interpreter's output and read from its input, which are stdout
+//! and stdin unless they were changed with `Interpreter::with_output` and `with_input`
+//!
+//! The input builtins give `Nil` once the input has run out.
+
+use super::{pop_string, string};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, Value};
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("print", print);
+    ctx.builtin_func("println", println);
+    ctx.builtin_func("write", write);
+    ctx.builtin_func("read_line", read_line);
+    ctx.builtin_func("read_number", read_number);
+    ctx.builtin_func("read_all", read_all);
+    ctx.builtin_func("prompt", prompt);
+}
+
+// x --
+fn print(interp: &mut Interpreter) -> Result<()> {
+    let value = interp.pop()?;
+    write!(interp.output(), "{}", value)?;
+    flush(interp)
+}
+
+// x --
+fn println(interp: &mut Interpreter) -> Result<()> {
+    let value = interp.pop()?;
+    writeln!(interp.output(), "{}", value)?;
+    flush(interp)
+}
+
+// x --
+fn write(interp: &mut Interpreter) -> Result<()> {
+    // written as in a program, so strings are quoted
+    let value = interp.pop()?;
+    write!(interp.output(), "{}", value.repr())?;
+    flush(interp)
+}
+
+// -- s
+fn read_line(interp: &mut Interpreter) -> Result<()> {
+    let line = next_line(interp)?;
+    interp.push(Value::from(line.map(Value::String)));
+    Ok(())
+}
+
+// -- n
+fn read_number(interp: &mut Interpreter) -> Result<()> {
+    // a line which isn't a number also gives `Nil`
+    let number = next_line(interp)?.map_or(Value::Nil, |line| string::number(&line));
+    interp.push(number);
+    Ok(())
+}
+
+// -- s
+fn read_all(interp: &mut Interpreter) -> Result<()> {
+    let mut s = String::new();
+    let rest = match interp.input().read_to_string(&mut s)? {
+        0 => Value::Nil,
+        _ => Value::String(s),
+    };
+    interp.push(rest);
+    Ok(())
+}
+
+// message -- s
+fn prompt(interp: &mut Interpreter) -> Result<()> {
+    let message = pop_string(interp)?;
+    write!(interp.output(), "{}", message)?;
+    flush(interp)?;
+    read_line(interp)
+}
+
+// flush after every write, so output which doesn't end a line is still seen straight away
+fn flush(interp: &mut Interpreter) -> Result<()> {
+    interp.output().flush()?;
+    Ok(())
+}
+
+// read a line without its line ending, or `None` at the end of the input
+fn next_line(interp: &mut Interpreter) -> Result<Option<String>> {
+    let mut line = String::new();
+    if interp.input().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(Some(line))
+}
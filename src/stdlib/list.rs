@@ -0,0 +1,102 @@
+//! List builtins, in addition to `len` and `get` which work on lists and maps
+//!
+//! `set`, `push` and `pop` change a list in place, which is seen through every variable
+//! holding it, while `concat`, `slice` and `reverse` create a new list.
+
+use std::convert::TryFrom;
+
+use super::{check_not_cyclic, new_list, pop_int, pop_list};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("set", set);
+    ctx.builtin_func("push", push);
+    ctx.builtin_func("pop", pop);
+    ctx.builtin_func("concat", concat);
+    ctx.builtin_func("slice", slice);
+    ctx.builtin_func("reverse", reverse);
+}
+
+// list i item --
+fn set(interp: &mut Interpreter) -> Result<()> {
+    let item = interp.pop()?;
+    let index = pop_int(interp)?;
+    let list = pop_list(interp)?;
+    check_not_cyclic(&Value::List(list.clone()), &item)?;
+    let mut list = list.borrow_mut();
+    let i = item_index(index, list.len())?;
+    list[i] = item;
+    Ok(())
+}
+
+// list item --
+fn push(interp: &mut Interpreter) -> Result<()> {
+    let item = interp.pop()?;
+    let list = pop_list(interp)?;
+    check_not_cyclic(&Value::List(list.clone()), &item)?;
+    list.borrow_mut().push(item);
+    Ok(())
+}
+
+// list -- item
+fn pop(interp: &mut Interpreter) -> Result<()> {
+    // popping from an empty list gives `Nil`
+    let item = pop_list(interp)?.borrow_mut().pop();
+    interp.push(Value::from(item));
+    Ok(())
+}
+
+// a b -- list
+fn concat(interp: &mut Interpreter) -> Result<()> {
+    let b = pop_list(interp)?;
+    let a = pop_list(interp)?;
+    let list = a
+        .borrow()
+        .iter()
+        .chain(b.borrow().iter())
+        .cloned()
+        .collect();
+    interp.push(new_list(list));
+    Ok(())
+}
+
+// list start end -- list
+fn slice(interp: &mut Interpreter) -> Result<()> {
+    let end = pop_int(interp)?;
+    let start = pop_int(interp)?;
+    let list = pop_list(interp)?;
+    let slice = {
+        let list = list.borrow();
+        let start = bound_index(start, list.len())?;
+        let end = bound_index(end, list.len())?;
+        // a range which ends before it starts is empty
+        list[start..end.max(start)].to_vec()
+    };
+    interp.push(new_list(slice));
+    Ok(())
+}
+
+// list -- list
+fn reverse(interp: &mut Interpreter) -> Result<()> {
+    let list = pop_list(interp)?;
+    let reversed = list.borrow().iter().rev().cloned().collect();
+    interp.push(new_list(reversed));
+    Ok(())
+}
+
+// convert `index` to the position of an item in a list of length `len`
+pub(super) fn item_index(index: i64, len: usize) -> Result<usize> {
+    match bound_index(index, len)? {
+        i if i < len => Ok(i),
+        _ => Err(InterpreterError::IndexOutOfRange(index, len).into()),
+    }
+}
+
+// convert `index` to the bound of a range in a list of length `len`, which may be the length
+pub(super) fn bound_index(index: i64, len: usize) -> Result<usize> {
+    match usize::try_from(index) {
+        Ok(i) if i <= len => Ok(i),
+        _ => Err(InterpreterError::IndexOutOfRange(index, len).into()),
+    }
+}
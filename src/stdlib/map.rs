@@ -0,0 +1,56 @@
+//! Map builtins, in addition to `len` and `get` which work on lists and maps
+//!
+//! `put` and `remove` change a map in place, which is seen through every variable holding it.
+
+use super::{check_not_cyclic, new_list, pop_map};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, Value};
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("put", put);
+    ctx.builtin_func("has", has);
+    ctx.builtin_func("remove", remove);
+    ctx.builtin_func("keys", keys);
+    ctx.builtin_func("values", values);
+}
+
+// map key value --
+fn put(interp: &mut Interpreter) -> Result<()> {
+    let value = interp.pop()?;
+    let key = interp.pop()?;
+    let map = pop_map(interp)?;
+    check_not_cyclic(&Value::Map(map.clone()), &value)?;
+    map.borrow_mut().insert(key, value)?;
+    Ok(())
+}
+
+// map key -- bool
+fn has(interp: &mut Interpreter) -> Result<()> {
+    let key = interp.pop()?;
+    let has = pop_map(interp)?.borrow().contains_key(&key)?;
+    interp.push(Value::Boolean(has));
+    Ok(())
+}
+
+// map key -- value
+fn remove(interp: &mut Interpreter) -> Result<()> {
+    // removing a missing key gives `Nil`
+    let key = interp.pop()?;
+    let value = pop_map(interp)?.borrow_mut().remove(&key)?;
+    interp.push(Value::from(value));
+    Ok(())
+}
+
+// map -- list
+fn keys(interp: &mut Interpreter) -> Result<()> {
+    let keys = pop_map(interp)?.borrow().keys().cloned().collect();
+    interp.push(new_list(keys));
+    Ok(())
+}
+
+// map -- list
+fn values(interp: &mut Interpreter) -> Result<()> {
+    let values = pop_map(interp)?.borrow().values().cloned().collect();
+    interp.push(new_list(values));
+    Ok(())
+}
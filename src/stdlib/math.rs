@@ -0,0 +1,176 @@
+//! Math functions and constants
+//!
+//! Functions such as `sqrt` and `sin` accept any number and give a float, which is checked
+//! against the interpreter's numeric policy. Rounding, `abs`, `min`, `max`, `clamp` and `pow`
+//! with an int exponent keep ints and fractions exact.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::f64::consts;
+
+use super::{expected, pop_float};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+use crate::num::{BigInt, Rational};
+
+// the most bits an exact power may have, so that a huge exponent is an error rather than taking
+// forever to compute
+const MAX_POW_BITS: u64 = 1 << 16;
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_const("PI", Value::Float(consts::PI));
+    ctx.builtin_const("TAU", Value::Float(2.0 * consts::PI));
+    ctx.builtin_const("E", Value::Float(consts::E));
+    ctx.builtin_const("INF", Value::Float(f64::INFINITY));
+    ctx.builtin_const("NAN", Value::Float(f64::NAN));
+
+    // x -- float
+    ctx.builtin_func("sqrt", |interp| float_fn(interp, "sqrt", f64::sqrt));
+    ctx.builtin_func("exp", |interp| float_fn(interp, "exp", f64::exp));
+    ctx.builtin_func("ln", |interp| float_fn(interp, "ln", f64::ln));
+    ctx.builtin_func("log10", |interp| float_fn(interp, "log10", f64::log10));
+    ctx.builtin_func("sin", |interp| float_fn(interp, "sin", f64::sin));
+    ctx.builtin_func("cos", |interp| float_fn(interp, "cos", f64::cos));
+    ctx.builtin_func("tan", |interp| float_fn(interp, "tan", f64::tan));
+    ctx.builtin_func("asin", |interp| float_fn(interp, "asin", f64::asin));
+    ctx.builtin_func("acos", |interp| float_fn(interp, "acos", f64::acos));
+    ctx.builtin_func("atan", |interp| float_fn(interp, "atan", f64::atan));
+
+    // x -- n
+    ctx.builtin_func("floor", |interp| {
+        round_fn(interp, Rational::floor, f64::floor)
+    });
+    ctx.builtin_func("ceil", |interp| round_fn(interp, Rational::ceil, f64::ceil));
+    ctx.builtin_func("round", |interp| {
+        round_fn(interp, Rational::round, f64::round)
+    });
+    ctx.builtin_func("trunc", |interp| {
+        round_fn(interp, Rational::trunc, f64::trunc)
+    });
+
+    ctx.builtin_func("abs", abs);
+    ctx.builtin_func("pow", pow);
+    ctx.builtin_func("hypot", hypot);
+    ctx.builtin_func("min", |interp| extremum(interp, Ordering::Less));
+    ctx.builtin_func("max", |interp| extremum(interp, Ordering::Greater));
+    ctx.builtin_func("clamp", clamp);
+}
+
+// x -- float
+fn float_fn(interp: &mut Interpreter, name: &'static str, f: fn(f64) -> f64) -> Result<()> {
+    let x = pop_float(interp)?;
+    let result = interp.numeric_policy().check(name, &[x], f(x))?;
+    interp.push(Value::Float(result));
+    Ok(())
+}
+
+// x -- n
+fn round_fn(
+    interp: &mut Interpreter,
+    exact: fn(&Rational) -> BigInt,
+    float: fn(f64) -> f64,
+) -> Result<()> {
+    // floats stay floats, use `int` to convert the result to an int
+    let result = match interp.pop()? {
+        Value::Float(n) => Value::Float(float(n)),
+        value => {
+            let n = value.as_exact().ok_or_else(|| expected("number", &value))?;
+            Value::from(exact(&n))
+        }
+    };
+    interp.push(result);
+    Ok(())
+}
+
+// x -- x
+fn abs(interp: &mut Interpreter) -> Result<()> {
+    let result = match interp.pop()? {
+        Value::Float(n) => Value::Float(n.abs()),
+        value => {
+            let n = value.as_exact().ok_or_else(|| expected("number", &value))?;
+            Value::from(n.abs())
+        }
+    };
+    interp.push(result);
+    Ok(())
+}
+
+// base exponent -- n
+fn pow(interp: &mut Interpreter) -> Result<()> {
+    let exponent = interp.pop()?;
+    let base = interp.pop()?;
+
+    // an exact base raised to an int stays exact, e.g. `2 -2 pow` is `1/4`
+    if let (Some(base), Value::Int(exp)) = (base.as_exact(), &exponent) {
+        if let Ok(power) = u32::try_from(exp.unsigned_abs()) {
+            if *exp < 0 && base.is_zero() {
+                return Err(InterpreterError::DivisionByZero.into());
+            }
+            // the result has about `power` times as many bits as the base. 0, 1 and -1 keep the
+            // same size however large the power
+            let bits = base.numer().bits().max(base.denom().bits()) - 1;
+            if (bits as u64).saturating_mul(u64::from(power)) > MAX_POW_BITS {
+                return Err(InterpreterError::TooLarge("number").into());
+            }
+            let result = base.pow(power);
+            let result = if *exp < 0 {
+                &Rational::from(1) / &result
+            } else {
+                result
+            };
+            interp.push(Value::from(result));
+            return Ok(());
+        }
+    }
+
+    let x = base.as_float().ok_or_else(|| expected("number", &base))?;
+    let y = exponent
+        .as_float()
+        .ok_or_else(|| expected("number", &exponent))?;
+    let result = interp.numeric_policy().check("pow", &[x, y], x.powf(y))?;
+    interp.push(Value::Float(result));
+    Ok(())
+}
+
+// x y -- float
+fn hypot(interp: &mut Interpreter) -> Result<()> {
+    let y = pop_float(interp)?;
+    let x = pop_float(interp)?;
+    let result = interp
+        .numeric_policy()
+        .check("hypot", &[x, y], x.hypot(y))?;
+    interp.push(Value::Float(result));
+    Ok(())
+}
+
+// a b -- a or b
+fn extremum(interp: &mut Interpreter, keep: Ordering) -> Result<()> {
+    let b = interp.pop()?;
+    let a = interp.pop()?;
+    // `a` is kept if they are equal
+    let result = if order(&b, &a)? == keep { b } else { a };
+    interp.push(result);
+    Ok(())
+}
+
+// x lo hi -- x
+fn clamp(interp: &mut Interpreter) -> Result<()> {
+    let hi = interp.pop()?;
+    let lo = interp.pop()?;
+    let x = interp.pop()?;
+    let result = if order(&x, &lo)? == Ordering::Less {
+        lo
+    } else if order(&x, &hi)? == Ordering::Greater {
+        hi
+    } else {
+        x
+    };
+    interp.push(result);
+    Ok(())
+}
+
+// order two numbers or two strings
+fn order(a: &Value, b: &Value) -> Result<Ordering> {
+    a.compare(b)
+        .ok_or_else(|| InterpreterError::Incomparable(a.type_name(), b.type_name()).into())
+}
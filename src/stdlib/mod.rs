@@ -0,0 +1,122 @@
+//! Builtin functions which are available to every frothy program
+//!
+//! Builtins take their arguments from the interpreter's stack and push their results back to
+//! it, so they are called like any other function, e.g. `[ 1 2 3 ] len call`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::{Error, Result};
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+use crate::map::Map;
+
+mod higher_order;
+mod io;
+mod list;
+mod map;
+mod math;
+mod number;
+mod string;
+
+/// Register every builtin as a constant in `ctx`
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("len", len);
+    ctx.builtin_func("get", get);
+    list::register(ctx);
+    map::register(ctx);
+    higher_order::register(ctx);
+    number::register(ctx);
+    math::register(ctx);
+    string::register(ctx);
+    io::register(ctx);
+}
+
+// collection -- n
+fn len(interp: &mut Interpreter) -> Result<()> {
+    let len = match interp.pop()? {
+        Value::List(list) => list.borrow().len(),
+        Value::Map(map) => map.borrow().len(),
+        // the number of characters rather than bytes
+        Value::String(s) => s.chars().count(),
+        value => return Err(expected("list, map or string", &value)),
+    };
+    interp.push(Value::Int(len as i64));
+    Ok(())
+}
+
+// list i -- item
+// map key -- value
+fn get(interp: &mut Interpreter) -> Result<()> {
+    let key = interp.pop()?;
+    let item = match interp.pop()? {
+        Value::List(list) => {
+            let list = list.borrow();
+            let index = match key {
+                Value::Int(n) => n,
+                key => return Err(expected("int", &key)),
+            };
+            list[list::item_index(index, list.len())?].clone()
+        }
+        // a missing key gives `Nil`
+        Value::Map(map) => Value::from(map.borrow().get(&key)?.cloned()),
+        value => return Err(expected("list or map", &value)),
+    };
+    interp.push(item);
+    Ok(())
+}
+
+// fail if adding `item` to `collection` would make the collection hold itself, which could
+// never be printed or compared
+fn check_not_cyclic(collection: &Value, item: &Value) -> Result<()> {
+    if item.holds(collection) {
+        return Err(InterpreterError::SelfContaining(collection.type_name()).into());
+    }
+    Ok(())
+}
+
+// pop a value which must be an int
+fn pop_int(interp: &mut Interpreter) -> Result<i64> {
+    match interp.pop()? {
+        Value::Int(n) => Ok(n),
+        value => Err(expected("int", &value)),
+    }
+}
+
+// pop any number, converted to a float
+fn pop_float(interp: &mut Interpreter) -> Result<f64> {
+    let value = interp.pop()?;
+    value.as_float().ok_or_else(|| expected("number", &value))
+}
+
+// pop a value which must be a string
+fn pop_string(interp: &mut Interpreter) -> Result<String> {
+    match interp.pop()? {
+        Value::String(s) => Ok(s),
+        value => Err(expected("string", &value)),
+    }
+}
+
+// pop a value which must be a list
+fn pop_list(interp: &mut Interpreter) -> Result<Rc<RefCell<Vec<Value>>>> {
+    match interp.pop()? {
+        Value::List(list) => Ok(list),
+        value => Err(expected("list", &value)),
+    }
+}
+
+// pop a value which must be a map
+fn pop_map(interp: &mut Interpreter) -> Result<Rc<RefCell<Map>>> {
+    match interp.pop()? {
+        Value::Map(map) => Ok(map),
+        value => Err(expected("map", &value)),
+    }
+}
+
+fn new_list(items: Vec<Value>) -> Value {
+    Value::List(Rc::new(RefCell::new(items)))
+}
+
+// the error for an argument of the wrong type
+fn expected(expected: &'static str, got: &Value) -> Error {
+    InterpreterError::ExpectedType(expected, got.type_name()).into()
+}
@@ -0,0 +1,32 @@
+//! Conversions between ints and floats
+
+use super::expected;
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+use crate::num::Rational;
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("int", int);
+    ctx.builtin_func("float", float);
+}
+
+// n -- int
+fn int(interp: &mut Interpreter) -> Result<()> {
+    // the fraction is discarded, rounding towards zero
+    let int = match interp.pop()? {
+        Value::Float(n) => Rational::from_f64(n).ok_or(InterpreterError::NotAnInt(n))?,
+        value => value.as_exact().ok_or_else(|| expected("number", &value))?,
+    };
+    interp.push(Value::from(int.trunc()));
+    Ok(())
+}
+
+// n -- float
+fn float(interp: &mut Interpreter) -> Result<()> {
+    let value = interp.pop()?;
+    match value.as_float() {
+        Some(n) => interp.push(Value::Float(n)),
+        None => return Err(expected("number", &value)),
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! String builtins, in addition to `len` which also works on lists and maps
+//!
+//! Strings are never changed in place, so every builtin which edits one gives a new string.
+//! Positions count characters rather than bytes.
+
+use std::convert::TryFrom;
+
+use super::list::bound_index;
+use super::{new_list, pop_int, pop_list, pop_string};
+use crate::error::Result;
+use crate::eval::{Context, Interpreter, InterpreterError, Value};
+use crate::format;
+use crate::span::Spanned;
+use crate::token::{Token, Tokens};
+
+// the length in bytes of the longest string `repeat` builds, so that a huge count is an error
+// rather than running out of memory
+const MAX_REPEAT_LEN: usize = 1 << 30;
+
+pub(crate) fn register(ctx: &mut Context) {
+    ctx.builtin_func("upper", |interp| map_string(interp, |s| s.to_uppercase()));
+    ctx.builtin_func("lower", |interp| map_string(interp, |s| s.to_lowercase()));
+    ctx.builtin_func("trim", |interp| {
+        map_string(interp, |s| s.trim().to_string())
+    });
+    ctx.builtin_func("split", split);
+    ctx.builtin_func("join", join);
+    ctx.builtin_func("replace", replace);
+    ctx.builtin_func("contains", |interp| {
+        test_string(interp, |s, sub| s.contains(sub))
+    });
+    ctx.builtin_func("starts_with", |interp| {
+        test_string(interp, |s, prefix| s.starts_with(prefix))
+    });
+    ctx.builtin_func("ends_with", |interp| {
+        test_string(interp, |s, suffix| s.ends_with(suffix))
+    });
+    ctx.builtin_func("substr", substr);
+    ctx.builtin_func("repeat", repeat);
+    ctx.builtin_func("chars", chars);
+    ctx.builtin_func("to_string", to_string);
+    ctx.builtin_func("parse_number", parse_number);
+    ctx.builtin_func("format", format);
+}
+
+// s -- s
+fn map_string(interp: &mut Interpreter, f: fn(&str) -> String) -> Result<()> {
+    let s = pop_string(interp)?;
+    interp.push(Value::String(f(&s)));
+    Ok(())
+}
+
+// s sub -- bool
+fn test_string(interp: &mut Interpreter, f: fn(&str, &str) -> bool) -> Result<()> {
+    let sub = pop_string(interp)?;
+    let s = pop_string(interp)?;
+    interp.push(Value::Boolean(f(&s, &sub)));
+    Ok(())
+}
+
+// s sep -- list
+fn split(interp: &mut Interpreter) -> Result<()> {
+    let sep = pop_string(interp)?;
+    let s = pop_string(interp)?;
+    // an empty separator splits on runs of whitespace
+    let parts: Vec<Value> = if sep.is_empty() {
+        s.split_whitespace().map(Value::from).collect()
+    } else {
+        s.split(sep.as_str()).map(Value::from).collect()
+    };
+    interp.push(new_list(parts));
+    Ok(())
+}
+
+// list sep -- s
+fn join(interp: &mut Interpreter) -> Result<()> {
+    let sep = pop_string(interp)?;
+    let list = pop_list(interp)?;
+    // items which aren't strings are converted as by `to_string`
+    let joined = list
+        .borrow()
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(&sep);
+    interp.push(Value::String(joined));
+    Ok(())
+}
+
+// s from to -- s
+fn replace(interp: &mut Interpreter) -> Result<()> {
+    let to = pop_string(interp)?;
+    let from = pop_string(interp)?;
+    let s = pop_string(interp)?;
+    interp.push(Value::String(s.replace(from.as_str(), &to)));
+    Ok(())
+}
+
+// s start end -- s
+fn substr(interp: &mut Interpreter) -> Result<()> {
+    let end = pop_int(interp)?;
+    let start = pop_int(interp)?;
+    let s = pop_string(interp)?;
+    let len = s.chars().count();
+    let start = bound_index(start, len)?;
+    let end = bound_index(end, len)?;
+    // a range which ends before it starts is empty
+    let sub = s
+        .chars()
+        .skip(start)
+        .take(end.saturating_sub(start))
+        .collect();
+    interp.push(Value::String(sub));
+    Ok(())
+}
+
+// s n -- s
+fn repeat(interp: &mut Interpreter) -> Result<()> {
+    let n = pop_int(interp)?;
+    let s = pop_string(interp)?;
+    // a negative count gives an empty string
+    let n = usize::try_from(n).unwrap_or(0);
+    match s.len().checked_mul(n) {
+        Some(len) if len <= MAX_REPEAT_LEN => interp.push(Value::String(s.repeat(n))),
+        _ => return Err(InterpreterError::TooLarge("string").into()),
+    }
+    Ok(())
+}
+
+// s -- list
+fn chars(interp: &mut Interpreter) -> Result<()> {
+    let s = pop_string(interp)?;
+    let chars = s.chars().map(|c| Value::String(c.to_string())).collect();
+    interp.push(new_list(chars));
+    Ok(())
+}
+
+// x -- s
+fn to_string(interp: &mut Interpreter) -> Result<()> {
+    let value = interp.pop()?;
+    interp.push(Value::String(value.to_string()));
+    Ok(())
+}
+
+// s -- n
+fn parse_number(interp: &mut Interpreter) -> Result<()> {
+    let s = pop_string(interp)?;
+    interp.push(number(&s));
+    Ok(())
+}
+
+// the number written in `s` as in a program. a string which isn't exactly one number, such as
+// "1 2" or "one", gives `Nil`
+pub(super) fn number(s: &str) -> Value {
+    let s = s.trim();
+    match Tokens::new(s).next() {
+        Some(Ok(Spanned { node, span })) if span.end == s.len() => match node {
+            Token::Int(n) => Value::Int(n),
+            Token::BigInt(n) => Value::from(n),
+            Token::Rational(n) => Value::from(n),
+            Token::Float(n) => Value::Float(n),
+            _ => Value::Nil,
+        },
+        _ => Value::Nil,
+    }
+}
+
+// ... template -- s
+// list template -- s
+fn format(interp: &mut Interpreter) -> Result<()> {
+    let template = pop_string(interp)?;
+    let pieces = format::parse(&template)?;
+    let fields = format::count_fields(&pieces)?;
+
+    // with several placeholders the values may come from a list just below the template, or
+    // else there is one from the stack for each placeholder. a single placeholder always takes
+    // one value, so that a list can be formatted
+    let args = match interp.stack().last() {
+        Some(Value::List(_)) if fields > 1 => pop_list(interp)?.borrow().clone(),
+        _ => {
+            let mut args = (0..fields)
+                .map(|_| interp.pop())
+                .collect::<Result<Vec<_>>>()?;
+            args.reverse();
+            args
+        }
+    };
+
+    interp.push(Value::String(format::format(&pieces, &args)?));
+    Ok(())
+}
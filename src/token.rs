@@ -4,11 +4,36 @@ use std::fmt;
 use std::str;
 
 use crate::error::Result;
+use crate::format::{self, FormatError, Piece};
+use crate::num::{BigInt, Rational};
+use crate::span::{Span, Spanned};
 
 /// Errors produced whilst reading tokens
 #[derive(Debug, Clone)]
 pub enum TokenError {
     Unexpected(u8),
+    /// A radix prefix such as `0x` with no digits after it
+    MissingDigits(String),
+    /// A decimal point with no digits after it, e.g. `1.`
+    MissingFraction,
+    /// An exponent marker with no digits after it, e.g. `1e`
+    MissingExponent,
+    /// A second decimal point, e.g. `1.2.3`
+    ExtraDecimalPoint,
+    /// A character which is not a digit in the radix of the number, e.g. `0b102`
+    InvalidDigit(u8, u32),
+    /// A `_` digit separator which is not between two digits, e.g. `1__000` or `1_`
+    MisplacedSeparator,
+    /// A string literal with no closing `"`
+    UnterminatedString,
+    /// An unknown escape sequence in a string literal, e.g. `\q`
+    InvalidEscape(char),
+    /// A malformed `\u{...}` escape or one which is not a valid unicode scalar value
+    InvalidUnicodeEscape,
+    /// A rational literal with a denominator of zero, e.g. `1/0`
+    ZeroDenominator,
+    /// A float literal too large to be represented, e.g. `1e400`
+    FloatOutOfRange,
 }
 
 impl fmt::Display for TokenError {
@@ -21,42 +46,102 @@ impl fmt::Display for TokenError {
                 }
                 Ok(())
             }
+            TokenError::MissingDigits(prefix) => write!(f, "expected digits after '{}'", prefix),
+            TokenError::MissingFraction => f.write_str("expected digits after decimal point"),
+            TokenError::MissingExponent => f.write_str("expected digits in exponent"),
+            TokenError::ExtraDecimalPoint => f.write_str("number has more than one decimal point"),
+            TokenError::InvalidDigit(byte, radix) => {
+                let name = match radix {
+                    2 => "binary",
+                    8 => "octal",
+                    16 => "hexadecimal",
+                    _ => "decimal",
+                };
+                write!(
+                    f,
+                    "invalid digit '{}' in {} number",
+                    char::from(*byte),
+                    name
+                )
+            }
+            TokenError::MisplacedSeparator => {
+                f.write_str("digit separator '_' must be between two digits")
+            }
+            TokenError::UnterminatedString => f.write_str("unterminated string"),
+            TokenError::InvalidEscape(c) => write!(f, "unknown escape sequence '\\{}'", c),
+            TokenError::InvalidUnicodeEscape => f.write_str("invalid unicode escape"),
+            TokenError::ZeroDenominator => f.write_str("rational has a zero denominator"),
+            TokenError::FloatOutOfRange => f.write_str("float is too large to be represented"),
         }
     }
 }
 
 /// A frothy token
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Ident(String),
-    Number(f64),
+    Int(i64),
+    BigInt(BigInt),
+    Rational(Rational),
+    Float(f64),
+    String(String),
+    /// An interpolated string, e.g. `f"x = {x}"`
+    FormatString(Vec<Piece>),
     Plus,
     Minus,
     Multiply,
     Divide,
+    IntDivide,
     Equals,
+    NotEquals,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
     Assign,
+    Reassign,
     Modulo,
     OpenBrace,
     CloseBrace,
-    CreateFunction,
+    OpenBracket,
+    CloseBracket,
+    OpenMap,
+    Arrow,
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Token::Ident(ident) => f.write_str(ident),
-            Token::Number(number) => write!(f, "{}", number),
+            Token::Int(int) => write!(f, "{}", int),
+            Token::BigInt(int) => write!(f, "{}", int),
+            Token::Rational(rational) => write!(f, "{}", rational),
+            Token::Float(float) => write!(f, "{:?}", float),
+            Token::String(string) => write!(f, "{:?}", string),
+            Token::FormatString(pieces) => {
+                let template: String = pieces.iter().map(Piece::to_string).collect();
+                write!(f, "f{:?}", template)
+            }
             Token::Plus => f.write_str("+"),
             Token::Minus => f.write_str("-"),
             Token::Multiply => f.write_str("*"),
             Token::Divide => f.write_str("/"),
+            Token::IntDivide => f.write_str("//"),
             Token::Equals => f.write_str("=="),
+            Token::NotEquals => f.write_str("!="),
+            Token::Less => f.write_str("<"),
+            Token::LessEqual => f.write_str("<="),
+            Token::Greater => f.write_str(">"),
+            Token::GreaterEqual => f.write_str(">="),
             Token::Assign => f.write_str("="),
+            Token::Reassign => f.write_str(":="),
             Token::Modulo => f.write_str("%"),
             Token::OpenBrace => f.write_str("{"),
             Token::CloseBrace => f.write_str("}"),
-            Token::CreateFunction => f.write_str("fn"),
+            Token::OpenBracket => f.write_str("["),
+            Token::CloseBracket => f.write_str("]"),
+            Token::OpenMap => f.write_str("%{"),
+            Token::Arrow => f.write_str("->"),
         }
     }
 }
@@ -66,14 +151,35 @@ impl fmt::Display for Token {
 pub struct Tokens<'a> {
     input: &'a [u8],
     pos: usize,
+    // current line number and the byte offset at which it starts
+    line: usize,
+    line_start: usize,
 }
 
 impl<'a> Tokens<'a> {
-    /// Create an `Iterator<Item = Token>` for the given input program
+    /// Create an `Iterator<Item = Spanned<Token>>` for the given input program
     pub fn new(input: &'a str) -> Tokens<'a> {
         Tokens {
             input: input.as_bytes(),
             pos: 0,
+            line: 1,
+            line_start: 0,
+        }
+    }
+
+    /// An empty [`Span`](../span/struct.Span.html) at the current position
+    pub fn span_here(&self) -> Span {
+        // count characters rather than bytes by skipping utf-8 continuation bytes
+        let col = self.input[self.line_start..self.pos]
+            .iter()
+            .filter(|&&b| b & 0xc0 != 0x80)
+            .count();
+
+        Span {
+            start: self.pos,
+            end: self.pos,
+            line: self.line,
+            col: col + 1,
         }
     }
 
@@ -90,6 +196,14 @@ impl<'a> Tokens<'a> {
     fn back(&mut self) -> Option<u8> {
         if self.pos > 0 {
             self.pos -= 1;
+            // stepping back over a newline returns us to the end of the previous line
+            if self.input[self.pos] == b'\n' {
+                self.line -= 1;
+                self.line_start = self.input[..self.pos]
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |i| i + 1);
+            }
             Some(self.input[self.pos])
         } else {
             None
@@ -100,6 +214,10 @@ impl<'a> Tokens<'a> {
     fn next_byte(&mut self) -> Option<u8> {
         if let Some(byte) = self.peek() {
             self.pos += 1;
+            if byte == b'\n' {
+                self.line += 1;
+                self.line_start = self.pos;
+            }
             Some(byte)
         } else {
             None
@@ -126,18 +244,216 @@ impl<'a> Tokens<'a> {
         &self.input[start..self.pos]
     }
 
-    // read and parse a number, assuming the current byte is a valid number character
-    // TODO: add support for decimals
-    fn next_number(&mut self) -> Result<f64> {
-        let sign = if self.next_byte_if(|&b| b == b'-').is_some() {
-            -1.0
-        } else {
-            1.0
+    // read digits of the given radix into `out`, skipping `_` separators between them,
+    // and return how many digits were read
+    fn next_digits(&mut self, radix: u32, out: &mut String) -> Result<usize> {
+        let mut count = 0;
+        let mut separator = false;
+
+        loop {
+            match self.peek() {
+                Some(b) if char::from(b).is_digit(radix) => {
+                    out.push(char::from(b));
+                    count += 1;
+                    separator = false;
+                }
+                // separators must follow a digit
+                Some(b'_') if count > 0 && !separator => separator = true,
+                Some(b'_') => {
+                    self.next_byte();
+                    return Err(TokenError::MisplacedSeparator.into());
+                }
+                _ => break,
+            }
+            self.next_byte();
+        }
+
+        // separators must also be followed by a digit
+        if separator {
+            return Err(TokenError::MisplacedSeparator.into());
+        }
+
+        Ok(count)
+    }
+
+    // read and parse a number, assuming the current byte is a valid number character. it is a
+    // float if it has a fraction or exponent, a rational if it has a denominator, and an
+    // integer otherwise
+    //
+    // number: '-'? ( <radix prefix> <digits>
+    //              | <digits> ( '.' <digits> )? ( 'e' [+-]? <digits> )?
+    //              | <digits> '/' <digits> )
+    fn next_number(&mut self) -> Result<Token> {
+        // the sign is kept with the digits so that the most negative integer can be read
+        let mut digits = String::new();
+        if self.next_byte_if(|&b| b == b'-').is_some() {
+            digits.push('-');
+        }
+
+        // check for a 0x, 0o or 0b radix prefix
+        let radix = match (self.peek(), self.input.get(self.pos + 1)) {
+            (Some(b'0'), Some(b'x')) | (Some(b'0'), Some(b'X')) => 16,
+            (Some(b'0'), Some(b'o')) | (Some(b'0'), Some(b'O')) => 8,
+            (Some(b'0'), Some(b'b')) | (Some(b'0'), Some(b'B')) => 2,
+            _ => 10,
         };
 
-        let result: f64 = str::from_utf8(self.next_byte_while(u8::is_ascii_digit))?.parse()?;
+        let mut float = false;
+        let mut denom = None;
+
+        if radix == 10 {
+            self.next_digits(10, &mut digits)?;
+
+            // fraction
+            if self.next_byte_if(|&b| b == b'.').is_some() {
+                float = true;
+                digits.push('.');
+                if self.next_digits(10, &mut digits)? == 0 {
+                    return Err(TokenError::MissingFraction.into());
+                }
+            }
+
+            // exponent
+            if self.next_byte_if(|&b| b == b'e' || b == b'E').is_some() {
+                float = true;
+                digits.push('e');
+                if let Some(sign) = self.next_byte_if(|&b| b == b'+' || b == b'-') {
+                    digits.push(char::from(sign));
+                }
+                if self.next_digits(10, &mut digits)? == 0 {
+                    return Err(TokenError::MissingExponent.into());
+                }
+            }
+
+            // denominator: a '/' directly followed by digits, so `1/3` is a rational but `1 3 /`
+            // and `7//2` are not
+            let slash_digit = self.input.get(self.pos + 1).is_some_and(u8::is_ascii_digit);
+            if !float && self.peek() == Some(b'/') && slash_digit {
+                self.next_byte();
+                let mut denom_digits = String::new();
+                self.next_digits(10, &mut denom_digits)?;
+                denom = Some(denom_digits);
+            }
+        } else {
+            // skip the prefix
+            let prefix = str::from_utf8(&self.input[self.pos..self.pos + 2])?.to_string();
+            self.pos += 2;
+
+            if self.next_digits(radix, &mut digits)? == 0 {
+                return Err(TokenError::MissingDigits(prefix).into());
+            }
+        }
+
+        // the number must not run into any other characters
+        match self.peek() {
+            Some(b'.') if radix == 10 => {
+                self.next_byte();
+                return Err(TokenError::ExtraDecimalPoint.into());
+            }
+            Some(b) if b.is_ascii_alphanumeric() || b == b'.' => {
+                self.next_byte();
+                return Err(TokenError::InvalidDigit(b, radix).into());
+            }
+            _ => {}
+        }
+
+        // the grammar above only accepts valid numbers, so they can't fail to parse
+        // a float which is too large rounds to infinity rather than failing to parse
+        if float {
+            let n: f64 = digits.parse()?;
+            if n.is_infinite() {
+                return Err(TokenError::FloatOutOfRange.into());
+            }
+            return Ok(Token::Float(n));
+        }
+        if let Ok(int) = i64::from_str_radix(&digits, radix) {
+            if denom.is_none() {
+                return Ok(Token::Int(int));
+            }
+        }
+
+        // integers which don't fit in an `i64` are read as `BigInt`s
+        let numer = BigInt::parse(&digits, radix).unwrap();
+        match denom {
+            Some(denom) => match BigInt::parse(&denom, 10).unwrap() {
+                denom if denom.is_zero() => Err(TokenError::ZeroDenominator.into()),
+                denom => Ok(Token::Rational(Rational::new(numer, denom))),
+            },
+            None => Ok(Token::BigInt(numer)),
+        }
+    }
+
+    // read a string literal, assuming the opening '"' has already been read
+    //
+    // strings may span multiple lines and contain the escapes \n \t \r \0 \\ \" and \u{...}
+    fn next_string(&mut self) -> Result<String> {
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.next_byte() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let escaped = match self.next_byte() {
+                        Some(b'n') => '\n',
+                        Some(b't') => '\t',
+                        Some(b'r') => '\r',
+                        Some(b'0') => '\0',
+                        Some(b'\\') => '\\',
+                        Some(b'"') => '"',
+                        Some(b'u') => self.next_unicode_escape()?,
+                        Some(_) => {
+                            // report the whole (possibly multi-byte) character
+                            self.back();
+                            let rest = str::from_utf8(&self.input[self.pos..])?;
+                            let c = rest.chars().next().unwrap();
+                            return Err(TokenError::InvalidEscape(c).into());
+                        }
+                        None => return Err(TokenError::UnterminatedString.into()),
+                    };
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buf).as_bytes());
+                }
+                Some(b) => bytes.push(b),
+                None => return Err(TokenError::UnterminatedString.into()),
+            }
+        }
+
+        Ok(str::from_utf8(&bytes)?.to_string())
+    }
+
+    // read an interpolated string, assuming the 'f"' has already been consumed
+    fn next_format_string(&mut self) -> Result<Vec<Piece>> {
+        let pieces = format::parse(&self.next_string()?)?;
+        // every placeholder names a variable
+        for piece in &pieces {
+            if let Piece::Field(name, _) = piece {
+                let mut chars = name.chars();
+                let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid {
+                    return Err(FormatError::ExpectedName(name.clone()).into());
+                }
+            }
+        }
+        Ok(pieces)
+    }
+
+    // read the `{...}` part of a \u{...} escape and return the character it represents
+    fn next_unicode_escape(&mut self) -> Result<char> {
+        if self.next_byte_if(|&b| b == b'{').is_none() {
+            return Err(TokenError::InvalidUnicodeEscape.into());
+        }
 
-        Ok(result * sign)
+        let digits = str::from_utf8(self.next_byte_while(u8::is_ascii_hexdigit))?;
+        let code = match digits.len() {
+            1..=6 => u32::from_str_radix(digits, 16).ok(),
+            _ => None,
+        };
+
+        match (code.and_then(char::from_u32), self.next_byte()) {
+            (Some(c), Some(b'}')) => Ok(c),
+            _ => Err(TokenError::InvalidUnicodeEscape.into()),
+        }
     }
 
     // read and parse an ident, assuming the current byte is a valid ident character
@@ -150,46 +466,85 @@ impl<'a> Tokens<'a> {
 }
 
 impl<'a> Iterator for Tokens<'a> {
-    type Item = Result<Token>;
+    type Item = Result<Spanned<Token>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         // skip whitespace
         self.next_byte_while(u8::is_ascii_whitespace);
 
+        // remember where the token starts so that it can be given a span
+        let start = self.span_here();
+
         // match the next byte if there is one
-        self.next_byte().and_then(|b| match b {
+        let token = match self.next_byte()? {
             // skip comments
             b'#' => {
                 self.next_byte_while(|&b| b != b'\n');
-                self.next()
+                return self.next();
             }
-            // negative number or minus
+            // negative number, arrow or minus
             b'-' => match self.peek() {
                 Some(b'0'..=b'9') => {
                     self.back();
-                    Some(self.next_number().map(Token::Number))
+                    self.next_number()
+                }
+                Some(b'>') => {
+                    self.next_byte();
+                    Ok(Token::Arrow)
                 }
-                _ => Some(Ok(Token::Minus)),
+                _ => Ok(Token::Minus),
             },
             // simple tokens
-            b'+' => Some(Ok(Token::Plus)),
-            b'/' => Some(Ok(Token::Plus)),
-            b'*' => Some(Ok(Token::Multiply)),
-            b'{' => Some(Ok(Token::OpenBrace)),
-            b'}' => Some(Ok(Token::CloseBrace)),
-            b'=' => Some(Ok(Token::Assign)),
-            // number: <digit>+
+            b'+' => Ok(Token::Plus),
+            b'/' if self.next_byte_if(|&b| b == b'/').is_some() => Ok(Token::IntDivide),
+            b'/' => Ok(Token::Divide),
+            b'*' => Ok(Token::Multiply),
+            b'%' if self.next_byte_if(|&b| b == b'{').is_some() => Ok(Token::OpenMap),
+            b'%' => Ok(Token::Modulo),
+            b'{' => Ok(Token::OpenBrace),
+            b'}' => Ok(Token::CloseBrace),
+            b'[' => Ok(Token::OpenBracket),
+            b']' => Ok(Token::CloseBracket),
+            // operators which may be followed by '='
+            b'=' if self.next_byte_if(|&b| b == b'=').is_some() => Ok(Token::Equals),
+            b'=' => Ok(Token::Assign),
+            b':' if self.next_byte_if(|&b| b == b'=').is_some() => Ok(Token::Reassign),
+            b'!' if self.next_byte_if(|&b| b == b'=').is_some() => Ok(Token::NotEquals),
+            b'<' if self.next_byte_if(|&b| b == b'=').is_some() => Ok(Token::LessEqual),
+            b'<' => Ok(Token::Less),
+            b'>' if self.next_byte_if(|&b| b == b'=').is_some() => Ok(Token::GreaterEqual),
+            b'>' => Ok(Token::Greater),
+            // string: '"' <character or escape>* '"'
+            b'"' => self.next_string().map(Token::String),
+            // number: <digit> <number character>*
             b if b.is_ascii_digit() => {
                 self.back();
-                Some(self.next_number().map(Token::Number))
+                self.next_number()
+            }
+            // interpolated string: 'f' <string>
+            b'f' if self.peek() == Some(b'"') => {
+                self.next_byte();
+                self.next_format_string().map(Token::FormatString)
             }
             // ident: <alphabetic> <alphabetic or '_'>+
             b if b.is_ascii_alphabetic() => {
                 self.back();
-                Some(self.next_ident().map(Token::Ident))
+                self.next_ident().map(Token::Ident)
             }
             // unrecognized
-            b => Some(Err(TokenError::Unexpected(b).into())),
-        })
+            b => Err(TokenError::Unexpected(b).into()),
+        };
+
+        // the token covers everything consumed since `start`
+        let span = Span {
+            end: self.pos,
+            ..start
+        };
+
+        Some(
+            token
+                .map(|token| Spanned::new(token, span))
+                .map_err(|e| e.at(span)),
+        )
     }
 }
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+const MAKE_COUNTER: &str = "make_counter { count 0 = { count count 1 + := count } fn } fn =";
+const MAKE_ADDER: &str = "make_adder { n -> { x -> x n + } fn } fn =";
+
+fn ints(values: &[i64]) -> Vec<Value> {
+    values.iter().map(|&n| Value::Int(n)).collect()
+}
+
+#[test]
+fn counters_keep_their_own_state() {
+    let program = format!(
+        "{} a make_counter call = b make_counter call = a call a call b call a call",
+        MAKE_COUNTER
+    );
+    assert_eq!(values(&program), ints(&[1, 2, 1, 3]));
+}
+
+#[test]
+fn adders_capture_their_argument() {
+    let program = format!(
+        "{} add5 {{ 5 make_adder call }} = add7 {{ 7 make_adder call }} = 10 add5 call 10 add7 call",
+        MAKE_ADDER
+    );
+    assert_eq!(values(&program), ints(&[15, 17]));
+}
+
+#[test]
+fn closures_see_later_updates() {
+    assert_eq!(values("x 1 = f { x } fn = x 2 := f call"), ints(&[2]));
+}
+
+#[test]
+fn each_for_iteration_has_its_own_index() {
+    let program = "fs [ ] = i 0 3 { fs { i } fn push call } for \
+                   fs 0 get call call fs 1 get call call fs 2 get call call";
+    assert_eq!(values(program), ints(&[0, 1, 2]));
+}
+
+#[test]
+fn captured_variables_stay_local() {
+    let program = format!("{} c make_counter call = c call count", MAKE_COUNTER);
+    match interpreter_error(&program) {
+        InterpreterError::VariableUndefined(name) => assert_eq!(name, "count"),
+        e => panic!("expected an undefined variable but got {:?}", e),
+    }
+}
@@ -0,0 +1,44 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use frothy::error::Error;
+use frothy::eval::{InterpreterError, Value};
+
+/// Run `program` and return every value it leaves
+pub fn values(program: &str) -> Vec<Value> {
+    frothy::eval(program).unwrap_or_else(|e| panic!("{}: unexpected error {:?}", program, e))
+}
+
+/// Run `program`, which must leave exactly one value
+pub fn eval(program: &str) -> Value {
+    match values(program).as_slice() {
+        [value] => value.clone(),
+        values => panic!("{}: expected one value but got {:?}", program, values),
+    }
+}
+
+/// The error `program` failed with, without its location
+pub fn error(program: &str) -> Error {
+    failure(program, frothy::eval(program))
+}
+
+/// The error from `result`, the outcome of running `program`, without its location
+pub fn failure(program: &str, result: Result<Vec<Value>, Error>) -> Error {
+    match result {
+        Ok(values) => panic!("{}: expected an error but got {:?}", program, values),
+        Err(e) => e.inner().clone(),
+    }
+}
+
+/// The interpreter error `program` failed with
+pub fn interpreter_error(program: &str) -> InterpreterError {
+    match error(program) {
+        Error::Interpreter(e) => e,
+        e => panic!("{}: expected an interpreter error but got {:?}", program, e),
+    }
+}
+
+/// An integer which doesn't fit in an `i64`
+pub fn big(digits: &str) -> Value {
+    Value::BigInt(frothy::num::BigInt::parse(digits, 10).unwrap())
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{eval, interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn if_runs_its_block_when_true() {
+    assert_eq!(values("true { 1 } if false { 2 } if"), vec![Value::Int(1)]);
+}
+
+#[test]
+fn ifelse_chooses_a_block() {
+    let program = r#"size { x -> x 3 > { "big" } { "small" } ifelse } fn ="#;
+    assert_eq!(
+        values(&format!("{} 5 size call 1 size call", program)),
+        vec![string("big"), string("small")]
+    );
+}
+
+#[test]
+fn only_the_chosen_block_runs() {
+    // `undefined` would be an error if its block ran
+    assert_eq!(eval("false { undefined } if 3"), Value::Int(3));
+    assert_eq!(eval("true { 1 } { undefined } ifelse"), Value::Int(1));
+}
+
+#[test]
+fn conditions_must_be_booleans() {
+    for program in &["1 { 2 } if", r#""yes" { 1 } { 2 } ifelse"#, "Nil { 1 } if"] {
+        match interpreter_error(program) {
+            InterpreterError::ExpectedType("boolean", _) => {}
+            e => panic!("{}: expected a boolean but got {:?}", program, e),
+        }
+    }
+}
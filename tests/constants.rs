@@ -0,0 +1,60 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn assigns_to_constant(program: &str) -> bool {
+    match interpreter_error(program) {
+        InterpreterError::AssignToConstant(_) => true,
+        e => panic!(
+            "{}: expected an assignment to a constant but got {:?}",
+            program, e
+        ),
+    }
+}
+
+#[test]
+fn parameters_shadow_builtins() {
+    assert_eq!(
+        values("f { max -> max 1 + } fn = 3 f call"),
+        vec![Value::Int(4)]
+    );
+    assert_eq!(
+        values("g { map -> map } fn = 5 g call"),
+        vec![Value::Int(5)]
+    );
+}
+
+#[test]
+fn parameters_shadow_constants() {
+    assert_eq!(
+        values("M 1 const = h { M -> M } fn = 7 h call M"),
+        vec![Value::Int(7), Value::Int(1)]
+    );
+}
+
+#[test]
+fn constants_cant_be_reassigned() {
+    assert!(assigns_to_constant("M 1 const = M 2 ="));
+    assert!(assigns_to_constant("M 1 const = M 2 :="));
+    assert!(assigns_to_constant("PI 3 :="));
+}
+
+#[test]
+fn builtins_cant_be_reassigned() {
+    for program in &[
+        "PI 3 =",
+        "print 5 =",
+        "len 5 =",
+        "values [ 1 2 ] =",
+        "max 1 const =",
+    ] {
+        assert!(assigns_to_constant(program), "{}", program);
+    }
+}
+
+#[test]
+fn reassigning_a_constant_value_makes_a_constant() {
+    assert!(assigns_to_constant("x 1 = x 5 const := x 6 :="));
+    assert_eq!(values("x 1 = x 5 const := x"), vec![Value::Int(5)]);
+}
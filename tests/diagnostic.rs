@@ -0,0 +1,84 @@
+use frothy::diagnostic::Diagnostic;
+
+// render the error from running `program` without color
+fn render(program: &str) -> String {
+    let error = frothy::eval(program).unwrap_err();
+    Diagnostic::from(&error).render("test.fy", program, false)
+}
+
+#[test]
+fn underlines_the_span() {
+    assert_eq!(
+        render("1 2 +\ny 2 +"),
+        "\
+error: undefined variable 'y'
+ --> test.fy:2:1
+  |
+2 | y 2 +
+  | ^
+  |
+  = help: variables must be assigned before they are used, e.g. `x 5 =`
+"
+    );
+}
+
+#[test]
+fn tabs_line_up_with_carets() {
+    assert_eq!(
+        render("\t1\ty +"),
+        "\
+error: undefined variable 'y'
+ --> test.fy:1:4
+  |
+1 |     1    y +
+  |          ^
+  |
+  = help: variables must be assigned before they are used, e.g. `x 5 =`
+"
+    );
+    assert_eq!(
+        render("1\t\"a\" +"),
+        "\
+error: cannot apply '+' to int and string
+ --> test.fy:1:1
+  |
+1 | 1    \"a\" +
+  | ^^^^^^^^^^
+  |
+  = help: `+` adds two numbers or joins two strings
+"
+    );
+}
+
+#[test]
+fn gutter_is_as_wide_as_the_line_number() {
+    let program = format!("{}\"é\" z", "\n".repeat(11));
+    assert_eq!(
+        render(&program),
+        "\
+error: undefined variable 'z'
+  --> test.fy:12:5
+   |
+12 | \"é\" z
+   |     ^
+   |
+   = help: variables must be assigned before they are used, e.g. `x 5 =`
+"
+    );
+}
+
+#[test]
+fn multiline_spans_stop_at_the_end_of_the_line() {
+    assert_eq!(
+        render("1 \"a\"\n+"),
+        "\
+error: cannot apply '+' to int and string
+ --> test.fy:1:1
+  |
+1 | 1 \"a\"
+  | ^^^^^
+  |
+  = help: `+` adds two numbers or joins two strings
+"
+    );
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use common::eval;
+use frothy::error::Error;
+use frothy::eval::Value;
+use frothy::format::{self, Align, FormatError, FormatSpec, Piece};
+
+fn spec(s: &str) -> FormatSpec {
+    FormatSpec::parse(s).unwrap()
+}
+
+fn format(program: &str) -> String {
+    match eval(program) {
+        Value::String(s) => s,
+        value => panic!("{}: expected a string but got {:?}", program, value),
+    }
+}
+
+fn error(program: &str) -> FormatError {
+    match common::error(program) {
+        Error::Format(e) => e,
+        e => panic!("{}: expected a format error but got {:?}", program, e),
+    }
+}
+
+#[test]
+fn parse_spec() {
+    assert_eq!(spec(""), FormatSpec::default());
+    assert_eq!(spec(">8").align, Some(Align::Right));
+    assert_eq!(spec(">8").width, 8);
+    assert_eq!(spec(".2").precision, Some(2));
+
+    let full = spec("*^10.3");
+    assert_eq!(full.fill, '*');
+    assert_eq!(full.align, Some(Align::Center));
+    assert_eq!(full.width, 10);
+    assert_eq!(full.precision, Some(3));
+
+    // a fill character which is itself an alignment
+    let fill = spec("<<4");
+    assert_eq!(fill.fill, '<');
+    assert_eq!(fill.align, Some(Align::Left));
+    assert_eq!(fill.width, 4);
+}
+
+#[test]
+fn spec_displays_as_parsed() {
+    for s in &["", ">8", ".2", "*^10.3", "<<4", "<", "5"] {
+        assert_eq!(spec(s).to_string(), *s);
+    }
+}
+
+#[test]
+fn invalid_specs() {
+    for s in &["x", "8x", ".", ".x", "-5", "1.2.3"] {
+        match FormatSpec::parse(s) {
+            Err(FormatError::InvalidSpec(_)) => {}
+            result => panic!("{}: expected an invalid spec but got {:?}", s, result),
+        }
+    }
+    match FormatSpec::parse("99999999999999999999") {
+        Err(FormatError::CountTooLarge(_)) => {}
+        result => panic!("expected a count too large but got {:?}", result),
+    }
+    assert!(FormatSpec::parse(&format::MAX_COUNT.to_string()).is_ok());
+}
+
+#[test]
+fn parse_template() {
+    let pieces = format::parse("{{a}} {x:>3} {}").unwrap();
+    assert_eq!(
+        pieces,
+        vec![
+            Piece::Text("{a} ".to_string()),
+            Piece::Field("x".to_string(), spec(">3")),
+            Piece::Text(" ".to_string()),
+            Piece::Field(String::new(), FormatSpec::default()),
+        ]
+    );
+    let written: String = pieces.iter().map(Piece::to_string).collect();
+    assert_eq!(written, "{{a}} {x:>3} {}");
+
+    assert!(matches!(format::parse("{"), Err(FormatError::Unclosed)));
+    assert!(matches!(
+        format::parse("}"),
+        Err(FormatError::UnmatchedBrace)
+    ));
+}
+
+#[test]
+fn padding_and_precision() {
+    assert_eq!(format(r#"42 "[{:5}]" format call"#), "[   42]");
+    assert_eq!(format(r#""ab" "[{:5}]" format call"#), "[ab   ]");
+    assert_eq!(format(r#""ab" "[{:-^6}]" format call"#), "[--ab--]");
+    assert_eq!(format(r#""abcdef" "{:.3}" format call"#), "abc");
+    assert_eq!(format(r#"2.5 "{:.3}" format call"#), "2.500");
+}
+
+#[test]
+fn exact_numbers_round_exactly() {
+    assert_eq!(format(r#"1 3 / "{:.5}" format call"#), "0.33333");
+    assert_eq!(format(r#"-5 2 / "{:.0}" format call"#), "-3");
+    assert_eq!(format(r#"-1 200 / "{:.2}" format call"#), "-0.01");
+    assert_eq!(format(r#"7 "{:.2}" format call"#), "7.00");
+    assert_eq!(
+        format(r#"12345678901234567890123 "{:.1}" format call"#),
+        "12345678901234567890123.0"
+    );
+}
+
+#[test]
+fn argument_errors() {
+    match error(r#"1 2 [ 1 ] "{} {}" format call"#) {
+        FormatError::ArgumentCount(2, 1) => {}
+        e => panic!("expected an argument count error but got {:?}", e),
+    }
+    match error(r#"1 "{x}" format call"#) {
+        FormatError::NamedField(name) => assert_eq!(name, "x"),
+        e => panic!("expected a named field but got {:?}", e),
+    }
+}
+
+#[test]
+fn counts_at_the_limit() {
+    let limit = format::MAX_COUNT;
+    let float = format(&format!(r#"1.5 "{{:.{}}}" format call"#, limit));
+    assert_eq!(float.len(), limit + 2);
+    assert!(float.starts_with("1.5000"));
+
+    let exact = format(&format!(r#"1 3 / "{{:.{}}}" format call"#, limit));
+    assert_eq!(exact.len(), limit + 2);
+    assert!(exact.starts_with("0.3333") && exact.ends_with('3'));
+
+    let padded = format(&format!(r#"1 "{{:{}}}" format call"#, limit));
+    assert_eq!(padded.len(), limit);
+
+    let over = format!(r#"1.5 "{{:.{}}}" format call"#, limit + 1);
+    match error(&over) {
+        FormatError::CountTooLarge(_) => {}
+        e => panic!("expected a count too large but got {:?}", e),
+    }
+}
+
+#[test]
+fn lists_as_arguments() {
+    assert_eq!(format(r#"[ 7 "b" ] "{:<3}|{}" format call"#), "7  |b");
+    assert_eq!(format(r#"[ 1 2 ] "xs = {}" format call"#), "xs = [1 2]");
+    assert_eq!(format(r#"[ 7 ] "{}" format call"#), "[7]");
+    assert_eq!(format(r#"[ [ 1 ] [ 2 ] ] "{} {}" format call"#), "[1] [2]");
+    match error(r#"[ 1 2 3 ] "{} {}" format call"#) {
+        FormatError::ArgumentCount(2, 3) => {}
+        e => panic!("expected an argument count error but got {:?}", e),
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{error, interpreter_error, values};
+use frothy::ast::AstError;
+use frothy::error::Error;
+use frothy::eval::{InterpreterError, Value};
+
+#[test]
+fn parameters_are_bound_in_order() {
+    assert_eq!(
+        values("f { a b -> b a } fn = 1 2 f call"),
+        vec![Value::Int(2), Value::Int(1)]
+    );
+    assert_eq!(
+        values("area { r -> r r * 3 * } fn = 2 area call"),
+        vec![Value::Int(12)]
+    );
+}
+
+#[test]
+fn parameters_are_local_to_a_call() {
+    match interpreter_error("f { a -> a } fn = 1 f call drop a") {
+        InterpreterError::VariableUndefined(name) => assert_eq!(name, "a"),
+        e => panic!("expected an undefined variable but got {:?}", e),
+    }
+    // a parameter hides a global of the same name
+    assert_eq!(
+        values("a 10 = f { a -> a } fn = 1 f call a"),
+        vec![Value::Int(1), Value::Int(10)]
+    );
+}
+
+#[test]
+fn calls_check_the_number_of_arguments() {
+    match interpreter_error("f { a b -> a } fn = 1 f call") {
+        InterpreterError::ArityMismatch(2, 1) => {}
+        e => panic!("expected an arity mismatch but got {:?}", e),
+    }
+}
+
+#[test]
+fn parameters_must_be_names() {
+    match error("f { 1 -> 1 } fn =") {
+        Error::Ast(AstError::Expected(pattern)) => {
+            assert_eq!(pattern, "parameter names before '->'")
+        }
+        e => panic!("expected a parse error but got {:?}", e),
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{eval, interpreter_error};
+use frothy::eval::{InterpreterError, Value};
+
+fn arity_mismatch(program: &str) -> (usize, usize) {
+    match interpreter_error(program) {
+        InterpreterError::ArityMismatch(expected, got) => (expected, got),
+        e => panic!("{}: expected an arity mismatch but got {:?}", program, e),
+    }
+}
+
+#[test]
+fn map() {
+    assert_eq!(
+        eval("[ 3 1 2 ] { x -> x x * } fn map call"),
+        eval("[ 9 1 4 ]")
+    );
+    assert_eq!(eval("[ ] { x -> x x * } fn map call"), eval("[ ]"));
+    assert_eq!(arity_mismatch("[ 1 ] { a b -> a } fn map call"), (2, 1));
+    match interpreter_error("[ 1 ] { x -> } fn map call") {
+        InterpreterError::StackUnderflow => {}
+        e => panic!("expected a stack underflow but got {:?}", e),
+    }
+}
+
+#[test]
+fn filter() {
+    assert_eq!(
+        eval("[ 1 2 3 4 ] { x -> x 2 % 0 == } fn filter call"),
+        eval("[ 2 4 ]")
+    );
+    assert_eq!(eval("[ ] { x -> true } fn filter call"), eval("[ ]"));
+    assert_eq!(
+        arity_mismatch("[ 1 ] { a b -> true } fn filter call"),
+        (2, 1)
+    );
+    match interpreter_error("[ 1 ] { x -> x } fn filter call") {
+        InterpreterError::ExpectedType("boolean", "int") => {}
+        e => panic!("expected a boolean but got {:?}", e),
+    }
+}
+
+#[test]
+fn fold() {
+    assert_eq!(
+        eval("[ 1 2 3 ] 0 { acc x -> acc x + } fn fold call"),
+        Value::Int(6)
+    );
+    // a function without parameters works on the stack of the accumulator and item
+    assert_eq!(
+        eval(r#"[ "a" "b" ] "" { + } fn fold call"#),
+        eval(r#""ab""#)
+    );
+    assert_eq!(
+        eval("[ ] 5 { acc x -> acc x + } fn fold call"),
+        Value::Int(5)
+    );
+    assert_eq!(
+        arity_mismatch("[ 1 ] 0 { a b c -> a } fn fold call"),
+        (3, 2)
+    );
+    match interpreter_error(r#"[ 1 ] "a" { acc x -> acc x - } fn fold call"#) {
+        InterpreterError::TypeMismatch { op: "-", .. } => {}
+        e => panic!("expected a type mismatch but got {:?}", e),
+    }
+}
+
+#[test]
+fn sort_by() {
+    assert_eq!(
+        eval(r#"[ "ccc" "a" "bb" ] { s -> s len call } fn sort_by call"#),
+        eval(r#"[ "a" "bb" "ccc" ]"#)
+    );
+    // the sort is stable
+    assert_eq!(
+        eval(r#"[ "b" "a" "cc" ] { s -> s len call } fn sort_by call"#),
+        eval(r#"[ "b" "a" "cc" ]"#)
+    );
+    assert_eq!(eval("[ ] { x -> x } fn sort_by call"), eval("[ ]"));
+    assert_eq!(
+        arity_mismatch("[ 1 2 ] { a b -> a } fn sort_by call"),
+        (2, 1)
+    );
+    match interpreter_error(r#"[ 1 2 ] { x -> [ x ] } fn sort_by call"#) {
+        InterpreterError::Incomparable("list", "list") => {}
+        e => panic!("expected incomparable keys but got {:?}", e),
+    }
+}
+
+#[test]
+fn zip() {
+    assert_eq!(
+        eval(r#"[ 1 2 3 ] [ "a" "b" ] zip call"#),
+        eval(r#"[ [ 1 "a" ] [ 2 "b" ] ]"#)
+    );
+    assert_eq!(eval("[ ] [ 1 ] zip call"), eval("[ ]"));
+    match interpreter_error("1 [ 2 ] zip call") {
+        InterpreterError::ExpectedType("list", "int") => {}
+        e => panic!("expected a list but got {:?}", e),
+    }
+}
+
+#[test]
+fn each_any_all() {
+    assert_eq!(
+        eval("total 0 = [ 1 2 3 ] { x -> total total x + := } fn each call total"),
+        Value::Int(6)
+    );
+    assert_eq!(
+        eval("[ 1 2 ] { x -> x 1 > } fn any call"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("[ 1 2 ] { x -> x 1 > } fn all call"),
+        Value::Boolean(false)
+    );
+    assert_eq!(eval("[ ] { x -> false } fn all call"), Value::Boolean(true));
+    match interpreter_error("[ 1 ] 5 each call") {
+        InterpreterError::NotCallable(_) => {}
+        e => panic!("expected a value which isn't callable but got {:?}", e),
+    }
+}
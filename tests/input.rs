@@ -0,0 +1,59 @@
+use std::io::Cursor;
+
+use frothy::eval::{Interpreter, Value};
+use frothy::io::Capture;
+
+fn run(input: &str, program: &str) -> Vec<Value> {
+    Interpreter::new()
+        .with_input(Cursor::new(input.to_string()))
+        .interpret(program)
+        .unwrap()
+}
+
+#[test]
+fn read_line_strips_line_endings() {
+    let values = run("a\r\nb\nc", "read_line call read_line call read_line call");
+    assert_eq!(
+        values,
+        vec![Value::from("a"), Value::from("b"), Value::from("c")]
+    );
+}
+
+#[test]
+fn nil_at_eof() {
+    let values = run("", "read_line call read_number call read_all call");
+    assert_eq!(values, vec![Value::Nil, Value::Nil, Value::Nil]);
+}
+
+#[test]
+fn read_number_uses_the_number_grammar() {
+    let values = run(
+        " 42 \n0xff\n1/3\n2.5\nten\n",
+        "read_number call read_number call read_number call read_number call read_number call",
+    );
+    assert_eq!(values[..2], [Value::Int(42), Value::Int(255)]);
+    assert_eq!(values[2].to_string(), "1/3");
+    assert_eq!(values[3], Value::Float(2.5));
+    assert_eq!(values[4], Value::Nil);
+}
+
+#[test]
+fn read_all_reads_the_rest() {
+    let values = run(
+        "first\nsecond\nthird\n",
+        "read_line call drop read_all call",
+    );
+    assert_eq!(values, vec![Value::from("second\nthird\n")]);
+}
+
+#[test]
+fn prompt_writes_then_reads() {
+    let capture = Capture::new();
+    let values = Interpreter::new()
+        .with_input(Cursor::new("frothy\n"))
+        .with_output(capture.clone())
+        .interpret(r#""name? " prompt call"#)
+        .unwrap();
+    assert_eq!(values, vec![Value::from("frothy")]);
+    assert_eq!(capture.contents(), "name? ");
+}
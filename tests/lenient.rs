@@ -0,0 +1,48 @@
+mod common;
+
+use common::interpreter_error;
+use frothy::error::Error;
+use frothy::eval::{Interpreter, InterpreterError, Value};
+
+fn lenient(program: &str) -> Result<Vec<Value>, Error> {
+    Interpreter::new()
+        .with_lenient_types(true)
+        .interpret(program)
+}
+
+#[test]
+fn type_mismatches_give_nil() {
+    for program in &[
+        r#"1 "a" +"#,
+        r#""a" 2 -"#,
+        "1 true <",
+        "Nil 1 +",
+        "true 1 and",
+        r#""x" not"#,
+    ] {
+        assert_eq!(lenient(program).unwrap(), vec![Value::Nil], "{}", program);
+    }
+}
+
+#[test]
+fn nil_results_can_be_used() {
+    assert_eq!(
+        lenient(r#"1 "a" + Nil =="#).unwrap(),
+        vec![Value::Boolean(true)]
+    );
+}
+
+#[test]
+fn strict_by_default() {
+    match interpreter_error(r#"1 "a" +"#) {
+        InterpreterError::TypeMismatch { op: "+", .. } => {}
+        e => panic!("expected a type mismatch but got {:?}", e),
+    }
+}
+
+#[test]
+fn other_errors_are_still_errors() {
+    for program in &["1 0 /", "x", "1 +", "[ 1 ] 5 get call"] {
+        assert!(lenient(program).is_err(), "{}", program);
+    }
+}
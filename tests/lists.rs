@@ -0,0 +1,49 @@
+mod common;
+
+use common::{eval, interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn self_containing(program: &str) -> &'static str {
+    match interpreter_error(program) {
+        InterpreterError::SelfContaining(type_name) => type_name,
+        e => panic!(
+            "{}: expected a self-containing error but got {:?}",
+            program, e
+        ),
+    }
+}
+
+#[test]
+fn lists_are_shared() {
+    assert_eq!(
+        values("xs [ 1 ] = ys xs = ys 2 push call xs len call xs 1 get call"),
+        vec![Value::Int(2), Value::Int(2)]
+    );
+}
+
+#[test]
+fn a_list_cant_hold_itself() {
+    assert_eq!(self_containing("xs [ ] = xs xs push call"), "list");
+    assert_eq!(self_containing("xs [ 1 ] = xs 0 xs set call"), "list");
+    assert_eq!(
+        self_containing("xs [ ] = ys [ xs ] = xs ys push call"),
+        "list"
+    );
+    assert_eq!(
+        self_containing("xs [ ] = m %{ 1 xs } = xs m push call"),
+        "list"
+    );
+}
+
+#[test]
+fn a_list_can_hold_a_copy_of_itself() {
+    assert_eq!(
+        eval("xs [ 1 ] = xs xs [ ] concat call push call xs"),
+        eval("[ 1 [ 1 ] ]")
+    );
+    // the same list twice is not a cycle
+    assert_eq!(
+        eval("ys [ 2 ] = xs [ ] = xs ys push call xs ys push call xs"),
+        eval("[ [ 2 ] [ 2 ] ]")
+    );
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn outside_loop(program: &str) -> &'static str {
+    match interpreter_error(program) {
+        InterpreterError::OutsideLoop(word) => word,
+        e => panic!("{}: expected a loop error but got {:?}", program, e),
+    }
+}
+
+#[test]
+fn break_in_a_loop_inside_a_function() {
+    assert_eq!(
+        values("f { i 0 3 { i i 1 == { break } if } for } fn = f call"),
+        vec![Value::Int(0), Value::Int(1)]
+    );
+}
+
+#[test]
+fn break_cant_leave_a_function() {
+    assert_eq!(
+        outside_loop("b { break } fn = i 0 3 { i b call } for"),
+        "break"
+    );
+}
+
+#[test]
+fn continue_cant_leave_a_callback() {
+    let program = "b { continue } fn = i 0 3 { [ 1 ] { b call } fn each call i } for";
+    assert_eq!(outside_loop(program), "continue");
+}
@@ -0,0 +1,74 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+mod common;
+
+use common::{eval, interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn hash(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn equal_numbers_hash_the_same() {
+    for program in &[
+        "1 1.0 2 2 /",
+        "1/2 0.5 2 4 /",
+        "18446744073709551616 18446744073709551616.0 2 64 pow call",
+        "-3 -3.0 -6 2 /",
+    ] {
+        let values = values(program);
+        for value in &values[1..] {
+            assert_eq!(*value, values[0], "{}", program);
+            assert_eq!(hash(value), hash(&values[0]), "{}", program);
+        }
+    }
+}
+
+#[test]
+fn equal_numbers_are_one_key() {
+    let program = r#"m %{ 1 "a" 1.0 "b" 2 2 / "c" } = m len call m 1 get call m keys call"#;
+    assert_eq!(
+        values(program),
+        vec![Value::Int(1), Value::String("c".to_string()), eval("[ 1 ]")]
+    );
+    assert_eq!(
+        values(r#"m %{ 1/2 "half" } = m 0.5 get call"#),
+        vec![Value::String("half".to_string())]
+    );
+}
+
+#[test]
+fn nan_is_a_key() {
+    assert_eq!(values("m %{ NAN 1 } = m NAN get call"), vec![Value::Int(1)]);
+}
+
+#[test]
+fn different_types_are_different_keys() {
+    let values = values(r#"m %{ 1 "int" "1" "string" true "bool" } = m len call"#);
+    assert_eq!(values, vec![Value::Int(3)]);
+}
+
+#[test]
+fn a_map_cant_hold_itself() {
+    for program in &[
+        "m %{ } = m 1 m put call",
+        "m %{ } = xs [ m ] = m 1 xs put call",
+        "m %{ } = n %{ 1 m } = m 2 n put call",
+    ] {
+        match interpreter_error(program) {
+            InterpreterError::SelfContaining("map") => {}
+            e => panic!(
+                "{}: expected a self-containing error but got {:?}",
+                program, e
+            ),
+        }
+    }
+    assert_eq!(
+        eval("m %{ } = n %{ } = m 1 n put call m 2 n put call m len call"),
+        Value::Int(2)
+    );
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+#[test]
+fn ints_and_floats_are_distinct() {
+    assert_eq!(
+        values("1 1.0 2 3 + 2 3.0 + 9007199254740993 1 +"),
+        vec![
+            Value::Int(1),
+            Value::Float(1.0),
+            Value::Int(5),
+            Value::Float(5.0),
+            Value::Int(9007199254740994),
+        ]
+    );
+}
+
+#[test]
+fn integer_division() {
+    assert_eq!(
+        values("7 2 // -7 2 // 7.5 2 // 7 2 %"),
+        vec![
+            Value::Int(3),
+            Value::Int(-4),
+            Value::Float(3.0),
+            Value::Int(1),
+        ]
+    );
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(
+        values("3.7 int call -3.7 int call 3 float call 7 int call"),
+        vec![
+            Value::Int(3),
+            Value::Int(-3),
+            Value::Float(3.0),
+            Value::Int(7),
+        ]
+    );
+}
+
+#[test]
+fn conversion_errors() {
+    for program in &["NAN int call", "INF int call"] {
+        match interpreter_error(program) {
+            InterpreterError::NotAnInt(_) => {}
+            e => panic!(
+                "{}: expected a float which isn't an int but got {:?}",
+                program, e
+            ),
+        }
+    }
+    match interpreter_error(r#""3" int call"#) {
+        InterpreterError::ExpectedType("number", "string") => {}
+        e => panic!("expected a number but got {:?}", e),
+    }
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::failure;
+use frothy::error::Error;
+use frothy::eval::{Interpreter, InterpreterError, NumericPolicy, Value};
+
+fn run(policy: NumericPolicy, program: &str) -> Result<Vec<Value>, Error> {
+    Interpreter::new()
+        .with_numeric_policy(policy)
+        .interpret(program)
+}
+
+fn float(policy: NumericPolicy, program: &str) -> f64 {
+    match run(policy, program).unwrap().as_slice() {
+        [Value::Float(n)] => *n,
+        values => panic!("expected a float but got {:?}", values),
+    }
+}
+
+fn error(policy: NumericPolicy, program: &str) -> InterpreterError {
+    match failure(program, run(policy, program)) {
+        Error::Interpreter(e) => e,
+        e => panic!("expected an interpreter error but got {:?}", e),
+    }
+}
+
+#[test]
+fn strict_is_the_default() {
+    assert_eq!(Interpreter::new().numeric_policy(), NumericPolicy::Strict);
+    assert!(frothy::eval("1 0.0 /").is_err());
+}
+
+#[test]
+fn strict_float_division_by_zero() {
+    for program in &[
+        "1 0.0 /",
+        "1.0 0 /",
+        "0.0 0.0 /",
+        "1 -0.0 /",
+        "5.5 0.0 //",
+        "5.5 0.0 %",
+    ] {
+        match error(NumericPolicy::Strict, program) {
+            InterpreterError::DivisionByZero => {}
+            e => panic!("{}: expected division by zero but got {:?}", program, e),
+        }
+    }
+}
+
+#[test]
+fn strict_invalid_results() {
+    match error(NumericPolicy::Strict, "1e308 0.1 /") {
+        InterpreterError::InvalidNumber("/", n) => assert_eq!(n, f64::INFINITY),
+        e => panic!("expected an invalid number but got {:?}", e),
+    }
+}
+
+#[test]
+fn strict_allows_finite_results() {
+    assert_eq!(float(NumericPolicy::Strict, "7.0 2 /"), 3.5);
+    assert_eq!(float(NumericPolicy::Strict, "7.5 2 //"), 3.0);
+    assert_eq!(float(NumericPolicy::Strict, "-7.5 2 %"), -1.5);
+}
+
+#[test]
+fn exact_division_by_zero_is_always_an_error() {
+    for &policy in &[NumericPolicy::Strict, NumericPolicy::Ieee] {
+        for program in &["1 0 /", "1/2 0 //", "7 0 %"] {
+            match error(policy, program) {
+                InterpreterError::DivisionByZero => {}
+                e => panic!("{}: expected division by zero but got {:?}", program, e),
+            }
+        }
+    }
+}
+
+#[test]
+fn ieee_division_by_zero() {
+    assert_eq!(float(NumericPolicy::Ieee, "1 0.0 /"), f64::INFINITY);
+    assert_eq!(float(NumericPolicy::Ieee, "-1 0.0 /"), f64::NEG_INFINITY);
+    assert!(float(NumericPolicy::Ieee, "0.0 0.0 /").is_nan());
+    assert!(float(NumericPolicy::Ieee, "5.5 0.0 %").is_nan());
+}
+
+#[test]
+fn ieee_overflow() {
+    assert_eq!(float(NumericPolicy::Ieee, "1e308 0.1 /"), f64::INFINITY);
+}
+
+#[test]
+fn check_passes_on_non_finite_arguments() {
+    let inf = f64::INFINITY;
+    assert_eq!(
+        NumericPolicy::Strict.check("/", &[inf, 2.0], inf).unwrap(),
+        inf
+    );
+    assert!(NumericPolicy::Strict.check("/", &[1.0, 2.0], inf).is_err());
+    assert!(NumericPolicy::Strict
+        .check("/", &[inf, inf], f64::NAN)
+        .is_err());
+    assert!(NumericPolicy::Ieee
+        .check("/", &[inf, inf], f64::NAN)
+        .unwrap()
+        .is_nan());
+}
+
+#[test]
+fn strict_math_builtins() {
+    for &(program, op) in &[
+        ("-1 sqrt call", "sqrt"),
+        ("0 ln call", "ln"),
+        ("2 acos call", "acos"),
+        ("1000 exp call", "exp"),
+        ("0.0 -1.0 pow call", "pow"),
+        ("NAN floor call NAN sqrt call", "sqrt"),
+    ] {
+        match error(NumericPolicy::Strict, program) {
+            InterpreterError::InvalidNumber(name, _) => assert_eq!(name, op, "{}", program),
+            e => panic!("{}: expected an invalid number but got {:?}", program, e),
+        }
+    }
+    assert_eq!(float(NumericPolicy::Strict, "INF sqrt call"), f64::INFINITY);
+    assert_eq!(float(NumericPolicy::Strict, "16 sqrt call"), 4.0);
+}
+
+#[test]
+fn ieee_math_builtins() {
+    assert!(float(NumericPolicy::Ieee, "-1 sqrt call").is_nan());
+    assert_eq!(float(NumericPolicy::Ieee, "0 ln call"), f64::NEG_INFINITY);
+    assert_eq!(float(NumericPolicy::Ieee, "1000 exp call"), f64::INFINITY);
+    assert_eq!(
+        float(NumericPolicy::Ieee, "0.0 -1.0 pow call"),
+        f64::INFINITY
+    );
+}
+
+#[test]
+fn exact_pow_of_zero_is_always_checked() {
+    for &policy in &[NumericPolicy::Strict, NumericPolicy::Ieee] {
+        match error(policy, "0 -1 pow call") {
+            InterpreterError::DivisionByZero => {}
+            e => panic!("expected division by zero but got {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn exact_pow_is_limited_in_size() {
+    for &policy in &[NumericPolicy::Strict, NumericPolicy::Ieee] {
+        for program in &[
+            "2 65537 pow call",
+            "1/10 100000 pow call",
+            "3 4000000000 pow call",
+        ] {
+            match error(policy, program) {
+                InterpreterError::TooLarge("number") => {}
+                e => panic!("{}: expected a too large number but got {:?}", program, e),
+            }
+        }
+        for program in &[
+            "2 65536 pow call",
+            "-1 4000000000 pow call",
+            "0 4000000000 pow call",
+        ] {
+            assert!(run(policy, program).is_ok(), "{}", program);
+        }
+    }
+    assert_eq!(
+        float(NumericPolicy::Ieee, "2.0 100000 pow call"),
+        f64::INFINITY
+    );
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn booleans(values: &[bool]) -> Vec<Value> {
+    values.iter().map(|&b| Value::Boolean(b)).collect()
+}
+
+fn type_mismatch(program: &str) -> &'static str {
+    match interpreter_error(program) {
+        InterpreterError::TypeMismatch { op, .. } => op,
+        e => panic!("{}: expected a type mismatch but got {:?}", program, e),
+    }
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(
+        values("1 2 < 2 2 <= 3 2 > 1 2 >= 1 1.0 == 1 2 !="),
+        booleans(&[true, true, true, false, true, true])
+    );
+    assert_eq!(
+        values(r#""a" "b" < "b" "b" >= 1 "1" == [ 1 ] [ 1 ] =="#),
+        booleans(&[true, true, false, true])
+    );
+}
+
+#[test]
+fn boolean_operators() {
+    assert_eq!(
+        values("true false and true false or true not 1 2 < 2 3 < and"),
+        booleans(&[false, true, false, true])
+    );
+}
+
+#[test]
+fn remainder() {
+    assert_eq!(
+        values("7 3 % 7.5 2 %"),
+        vec![Value::Int(1), Value::Float(1.5)]
+    );
+}
+
+#[test]
+fn operators_need_matching_types() {
+    assert_eq!(type_mismatch(r#"1 "a" <"#), "<");
+    assert_eq!(type_mismatch("true false >="), ">=");
+    assert_eq!(type_mismatch("1 true and"), "and");
+    assert_eq!(type_mismatch(r#""a" 2 %"#), "%");
+    match interpreter_error("1 not") {
+        InterpreterError::ExpectedType("boolean", "int") => {}
+        e => panic!("expected a boolean but got {:?}", e),
+    }
+}
@@ -0,0 +1,42 @@
+use frothy::eval::Interpreter;
+use frothy::io::Capture;
+
+fn output(program: &str) -> String {
+    let capture = Capture::new();
+    let mut interp = Interpreter::new().with_output(capture.clone());
+    interp.run(program).unwrap();
+    capture.contents()
+}
+
+#[test]
+fn print_consumes_its_argument() {
+    let capture = Capture::new();
+    let mut interp = Interpreter::new().with_output(capture.clone());
+    let values = interp.run(r#"1 "a" print call"#).unwrap();
+    assert_eq!(values.len(), 1);
+    assert_eq!(capture.contents(), "a");
+}
+
+#[test]
+fn println_ends_the_line() {
+    assert_eq!(output(r#""a" println call 1/2 println call"#), "a\n1/2\n");
+}
+
+#[test]
+fn write_quotes_strings() {
+    assert_eq!(
+        output(r#""say \"hi\"" write call [ "x" 1 ] write call"#),
+        r#""say \"hi\""["x" 1]"#
+    );
+}
+
+#[test]
+fn output_is_shared_by_runs() {
+    let capture = Capture::new();
+    let mut interp = Interpreter::new().with_output(capture.clone());
+    interp.run("1 print call").unwrap();
+    interp.run("2 print call").unwrap();
+    assert_eq!(capture.contents(), "12");
+    capture.clear();
+    assert_eq!(capture.contents(), "");
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{big, eval};
+use frothy::eval::Value;
+
+#[test]
+fn overflow_gives_a_bigint() {
+    for &(program, result) in &[
+        ("9223372036854775807 1 +", "9223372036854775808"),
+        ("-9223372036854775808 1 -", "-9223372036854775809"),
+        ("4611686018427387904 2 *", "9223372036854775808"),
+        ("-9223372036854775808 -1 *", "9223372036854775808"),
+        ("-9223372036854775808 -1 /", "9223372036854775808"),
+        ("-9223372036854775808 -1 //", "9223372036854775808"),
+    ] {
+        assert_eq!(eval(program), big(result), "{}", program);
+    }
+}
+
+#[test]
+fn overflow_without_a_bigint_result() {
+    assert_eq!(eval("-9223372036854775808 -1 %"), Value::Int(0));
+}
+
+#[test]
+fn small_results_are_ints_again() {
+    assert_eq!(eval("9223372036854775807 1 + 1 -"), Value::Int(i64::MAX));
+    assert_eq!(
+        eval("18446744073709551616 4294967296 /"),
+        Value::Int(1 << 32)
+    );
+}
@@ -0,0 +1,24 @@
+use frothy::eval::{Interpreter, Value};
+
+#[test]
+fn run_keeps_variables() {
+    let mut interp = Interpreter::new();
+    interp.run("x 5 =").unwrap();
+    assert_eq!(interp.run("x 1 +").unwrap(), vec![Value::Int(6)]);
+}
+
+#[test]
+fn failed_run_clears_the_stack() {
+    let mut interp = Interpreter::new();
+    assert!(interp.run("1 2 3 x").is_err());
+    assert!(interp.stack().is_empty());
+    assert_eq!(interp.run("4").unwrap(), vec![Value::Int(4)]);
+}
+
+#[test]
+fn failed_parse_clears_the_stack() {
+    let mut interp = Interpreter::new();
+    interp.push(Value::Int(1));
+    assert!(interp.run("{").is_err());
+    assert_eq!(interp.run("2").unwrap(), vec![Value::Int(2)]);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn undefined(program: &str) -> String {
+    match interpreter_error(program) {
+        InterpreterError::VariableUndefined(name) => name,
+        e => panic!(
+            "{}: expected an undefined variable but got {:?}",
+            program, e
+        ),
+    }
+}
+
+#[test]
+fn assignments_are_local() {
+    assert_eq!(
+        values("x 1 = f { x 2 = x } fn = f call x"),
+        vec![Value::Int(2), Value::Int(1)]
+    );
+    assert_eq!(values("x 1 = true { x 5 = } if x"), vec![Value::Int(1)]);
+    assert_eq!(undefined("f { tmp 1 = } fn = f call tmp"), "tmp");
+}
+
+#[test]
+fn reassignment_updates_the_outer_variable() {
+    assert_eq!(
+        values("x 1 = f { x 2 := } fn = f call x"),
+        vec![Value::Int(2)]
+    );
+    assert_eq!(values("x 1 = true { x 5 := } if x"), vec![Value::Int(5)]);
+}
+
+#[test]
+fn lookups_walk_outwards() {
+    assert_eq!(
+        values("x 1 = f { true { x } if } fn = f call"),
+        vec![Value::Int(1)]
+    );
+}
+
+#[test]
+fn reassigning_an_undefined_variable() {
+    assert_eq!(undefined("y 3 :="), "y");
+    assert_eq!(undefined("f { z 1 := } fn = f call"), "z");
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use frothy::ast::{AstError, Parser};
+use frothy::error::Error;
+use frothy::span::Span;
+use frothy::token::Tokens;
+
+fn span(start: usize, end: usize, line: usize, col: usize) -> Span {
+    Span {
+        start,
+        end,
+        line,
+        col,
+    }
+}
+
+#[test]
+fn tokens_have_spans() {
+    let spans: Vec<Span> = Tokens::new("x 12 =\n  \"é\" +")
+        .map(|token| token.unwrap().span)
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            span(0, 1, 1, 1),
+            span(2, 4, 1, 3),
+            span(5, 6, 1, 6),
+            span(9, 13, 2, 3),
+            // columns count characters, so the two byte 'é' is one column
+            span(14, 15, 2, 7),
+        ]
+    );
+}
+
+#[test]
+fn asts_cover_their_operands() {
+    let asts = Parser::new("1 2 +\nx 3 =").parse().unwrap();
+    let spans: Vec<Span> = asts.iter().map(|ast| ast.span).collect();
+    assert_eq!(spans, vec![span(0, 5, 1, 1), span(6, 11, 2, 1)]);
+}
+
+#[test]
+fn errors_have_locations() {
+    let error = frothy::eval("1 2 +\n  y").unwrap_err();
+    assert_eq!(error.span(), Some(span(8, 9, 2, 3)));
+    assert_eq!(error.to_string(), "2:3: undefined variable 'y'");
+
+    let error = frothy::eval("1 }").unwrap_err();
+    assert_eq!(error.span().map(|span| (span.line, span.col)), Some((1, 3)));
+    match error.inner() {
+        Error::Ast(AstError::Unexpected(_)) => {}
+        e => panic!("expected an unexpected token but got {:?}", e),
+    }
+}
+
+#[test]
+fn token_errors_have_locations() {
+    let error = frothy::eval("1\n\n  0x").unwrap_err();
+    assert_eq!(error.span().map(|span| (span.line, span.col)), Some((3, 3)));
+    assert!(matches!(common::error("1\n\n  0x"), Error::Token(_)));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{interpreter_error, values};
+use frothy::eval::{InterpreterError, Value};
+
+fn ints(values: &[i64]) -> Vec<Value> {
+    values.iter().map(|&n| Value::Int(n)).collect()
+}
+
+#[test]
+fn stack_words() {
+    for &(program, result) in &[
+        ("1 2 dup", &[1, 2, 2][..]),
+        ("1 2 drop", &[1]),
+        ("1 2 swap", &[2, 1]),
+        ("1 2 over", &[1, 2, 1]),
+        ("1 2 3 rot", &[2, 3, 1]),
+        ("1 2 nip", &[2]),
+        ("1 2 tuck", &[2, 1, 2]),
+        ("1 2 3 depth", &[1, 2, 3, 3]),
+        ("1 2 clear 3", &[3]),
+    ] {
+        assert_eq!(values(program), ints(result), "{}", program);
+    }
+}
+
+#[test]
+fn stack_words_need_enough_values() {
+    for program in &["drop", "1 swap", "1 2 rot", "1 over", "clear dup"] {
+        match interpreter_error(program) {
+            InterpreterError::StackUnderflow => {}
+            e => panic!("{}: expected a stack underflow but got {:?}", program, e),
+        }
+    }
+}
+
+#[test]
+fn calls_take_arguments_from_the_stack() {
+    assert_eq!(values("sub { a b -> a b - } fn = 7 2 sub call"), ints(&[5]));
+    // a function without parameters works on the stack directly
+    assert_eq!(
+        values("sq { dup * } fn = 3 sq call 4 sq call"),
+        ints(&[9, 16])
+    );
+    // values below the arguments are left alone
+    assert_eq!(
+        values("inc { n -> n 1 + } fn = 1 2 inc call"),
+        ints(&[1, 3])
+    );
+    // a function may leave several values
+    assert_eq!(
+        values("divmod { a b -> a b // a b % } fn = 7 2 divmod call"),
+        ints(&[3, 1])
+    );
+}
+
+#[test]
+fn calls_need_enough_arguments() {
+    for &(program, expected, got) in &[
+        ("sub { a b -> a b - } fn = 2 sub call", 2, 1),
+        ("f { a -> a } fn = f call", 1, 0),
+    ] {
+        match interpreter_error(program) {
+            InterpreterError::ArityMismatch(e, g) => assert_eq!((e, g), (expected, got)),
+            e => panic!("{}: expected an arity mismatch but got {:?}", program, e),
+        }
+    }
+    match interpreter_error("sq { dup * } fn = sq call") {
+        InterpreterError::StackUnderflow => {}
+        e => panic!("expected a stack underflow but got {:?}", e),
+    }
+}
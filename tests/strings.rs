@@ -0,0 +1,52 @@
+mod common;
+
+use common::{eval, interpreter_error};
+use frothy::eval::{InterpreterError, Value};
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn substr_counts_characters() {
+    assert_eq!(eval(r#""héllo" 1 3 substr call"#), string("él"));
+    assert_eq!(eval(r#""héllo" 0 5 substr call"#), string("héllo"));
+    assert_eq!(eval(r#""héllo" 3 1 substr call"#), string(""));
+    match interpreter_error(r#""héllo" 0 6 substr call"#) {
+        InterpreterError::IndexOutOfRange(6, 5) => {}
+        e => panic!("expected an index out of range but got {:?}", e),
+    }
+}
+
+#[test]
+fn split() {
+    assert_eq!(
+        eval(r#"" a  b\tc " "" split call"#),
+        eval(r#"[ "a" "b" "c" ]"#)
+    );
+    assert_eq!(
+        eval(r#""a,b,,c" "," split call"#),
+        eval(r#"[ "a" "b" "" "c" ]"#)
+    );
+}
+
+#[test]
+fn parse_number() {
+    assert_eq!(eval(r#"" 0x10 " parse_number call"#), Value::Int(16));
+    assert_eq!(eval(r#""-2.5" parse_number call"#), Value::Float(-2.5));
+    assert_eq!(eval(r#""1/3" parse_number call"#), eval("1 3 /"));
+    for s in &["1 2", "one", "", "1e400"] {
+        let program = format!("{:?} parse_number call", s);
+        assert_eq!(eval(&program), Value::Nil, "{}", s);
+    }
+}
+
+#[test]
+fn repeat() {
+    assert_eq!(eval(r#""ab" 3 repeat call"#), string("ababab"));
+    assert_eq!(eval(r#""ab" -1 repeat call"#), string(""));
+    match interpreter_error(r#""ab" 9223372036854775807 repeat call"#) {
+        InterpreterError::TooLarge("string") => {}
+        e => panic!("expected a too large string but got {:?}", e),
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use frothy::error::Error;
+use frothy::token::{Token, TokenError, Tokens};
+
+fn tokens(program: &str) -> Vec<Token> {
+    Tokens::new(program)
+        .map(|token| token.unwrap().node)
+        .collect()
+}
+
+fn error(program: &str) -> TokenError {
+    match common::error(program) {
+        Error::Token(e) => e,
+        e => panic!("{}: expected a token error but got {:?}", program, e),
+    }
+}
+
+#[test]
+fn number_forms() {
+    assert_eq!(tokens("-0x10"), vec![Token::Int(-16)]);
+    assert_eq!(tokens("1_000"), vec![Token::Int(1000)]);
+    assert_eq!(tokens("0o17 0b1010"), vec![Token::Int(15), Token::Int(10)]);
+    assert_eq!(
+        tokens("1e-9 2.5E+3 1_0.5"),
+        vec![Token::Float(1e-9), Token::Float(2500.0), Token::Float(10.5)]
+    );
+}
+
+#[test]
+fn invalid_numbers() {
+    for &(program, expected) in &[
+        ("1.2.3", "number has more than one decimal point"),
+        ("0x", "expected digits after '0x'"),
+        ("0b102", "invalid digit '2' in binary number"),
+        ("1__0", "digit separator '_' must be between two digits"),
+        ("1_", "digit separator '_' must be between two digits"),
+        ("1e", "expected digits in exponent"),
+        ("1.", "expected digits after decimal point"),
+        ("1/0", "rational has a zero denominator"),
+    ] {
+        assert_eq!(error(program).to_string(), expected, "{}", program);
+    }
+}
+
+#[test]
+fn float_out_of_range() {
+    match error("1e400") {
+        TokenError::FloatOutOfRange => {}
+        e => panic!("expected an out of range float but got {:?}", e),
+    }
+    assert!(frothy::eval("-1e400").is_err());
+    assert_eq!(tokens("1e-400"), vec![Token::Float(0.0)]);
+}
+
+fn string(s: &str) -> Token {
+    Token::String(s.to_string())
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(
+        tokens(r#""a\nb\tc\\d\"e\r\0""#),
+        vec![string("a\nb\tc\\d\"e\r\0")]
+    );
+    assert_eq!(
+        tokens(r#""\u{41}\u{e9}\u{1F600}""#),
+        vec![string("A\u{e9}\u{1F600}")]
+    );
+    assert_eq!(tokens("\"two\nlines\""), vec![string("two\nlines")]);
+}
+
+#[test]
+fn invalid_escapes() {
+    match error(r#""\q""#) {
+        TokenError::InvalidEscape('q') => {}
+        e => panic!("expected an invalid escape but got {:?}", e),
+    }
+    match error(r#""\é""#) {
+        TokenError::InvalidEscape('é') => {}
+        e => panic!("expected an invalid escape but got {:?}", e),
+    }
+    for program in &[
+        r#""\u{41""#,
+        r#""\u{""#,
+        r#""\u41""#,
+        r#""\u{}""#,
+        r#""\u{1234567}""#,
+        r#""\u{D800}""#,
+        r#""\u{110000}""#,
+    ] {
+        match error(program) {
+            TokenError::InvalidUnicodeEscape => {}
+            e => panic!(
+                "{}: expected an invalid unicode escape but got {:?}",
+                program, e
+            ),
+        }
+    }
+    for program in &[r#""abc"#, r#""abc\"#, r#""\u{41}"#] {
+        match error(program) {
+            TokenError::UnterminatedString => {}
+            e => panic!(
+                "{}: expected an unterminated string but got {:?}",
+                program, e
+            ),
+        }
+    }
+}